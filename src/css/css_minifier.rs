@@ -0,0 +1,450 @@
+use crate::{
+    css::{
+        css_options::CssOptions,
+        values::{is_shortened_function, is_value_char, shorten_function, shorten_value},
+    },
+    io::stream::Stream,
+};
+
+/// At-rules whose block contains rules instead of declarations
+pub const RULE_LIST_AT_RULES: [&str; 8] = [
+    "container",
+    "document",
+    "-moz-document",
+    "layer",
+    "media",
+    "scope",
+    "starting-style",
+    "supports",
+];
+
+/// Content of a block
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Block {
+    Rules,
+    Declarations,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum State {
+    Normal,
+    Slash,
+    Comment,
+    CommentStar,
+    String(char),
+    StringEscape(char),
+    Escape,
+    EscapeHex(u8),
+    UrlStart,
+    Url,
+    UrlEscape,
+}
+
+/// Part of a value which is held back until it is complete
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Buffering {
+    None,
+    Value,
+    Function(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct CssMinifier {
+    options: CssOptions,
+    state: State,
+    blocks: Vec<Block>,
+    /// Whether each open parenthesis was opened in a selector
+    parens: Vec<bool>,
+    /// Set while the statement in a declaration block is the selector of a
+    /// nested rule
+    is_nested_selector: bool,
+    last: Option<char>,
+    whitespace: bool,
+    semicolon: bool,
+    statement_start: bool,
+    is_at_rule: bool,
+    at_rule: String,
+    word: String,
+    in_value: bool,
+    property: String,
+    buffering: Buffering,
+    buffer: String,
+}
+
+impl Default for CssMinifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CssMinifier {
+    /// Creates a minifier for style sheets
+    pub fn new() -> Self {
+        Self::with_options(CssOptions::new())
+    }
+
+    /// Creates a minifier for style sheets with the given options
+    pub fn with_options(options: CssOptions) -> Self {
+        Self::with_block(options, Block::Rules)
+    }
+
+    /// Creates a minifier for declarations, like the content of a `style`
+    /// attribute
+    pub fn inline(options: CssOptions) -> Self {
+        Self::with_block(options, Block::Declarations)
+    }
+
+    fn with_block(options: CssOptions, block: Block) -> Self {
+        Self {
+            options,
+            state: State::Normal,
+            blocks: vec![block],
+            parens: Vec::new(),
+            is_nested_selector: false,
+            last: None,
+            whitespace: false,
+            semicolon: false,
+            statement_start: true,
+            is_at_rule: false,
+            at_rule: String::new(),
+            word: String::new(),
+            in_value: false,
+            property: String::new(),
+            buffering: Buffering::None,
+            buffer: String::new(),
+        }
+    }
+
+    fn is_declaration_block(&self) -> bool {
+        self.blocks.last() == Some(&Block::Declarations)
+    }
+
+    fn emit(&mut self, item: char, out: &mut String) {
+        if self.buffering == Buffering::None {
+            out.push(item);
+        } else {
+            self.buffer.push(item);
+        }
+        self.last = Some(item);
+    }
+
+    /// Writes the held back value in its shortest form
+    fn flush(&mut self, out: &mut String) {
+        let value = match self.buffering {
+            Buffering::None => return,
+            Buffering::Value => shorten_value(
+                self.options,
+                &self.property,
+                !self.parens.is_empty(),
+                &self.buffer,
+            ),
+            Buffering::Function(_) => shorten_function(self.options, &self.buffer),
+        };
+        self.buffering = Buffering::None;
+        self.buffer.clear();
+        out.push_str(&value);
+        if let Some(last) = value.chars().last() {
+            self.last = Some(last);
+        }
+    }
+
+    /// Checks whether the current position is part of a selector
+    fn is_selector(&self) -> bool {
+        self.parens
+            .last()
+            .copied()
+            .unwrap_or_else(|| !self.is_declaration_block() || self.is_nested_selector)
+    }
+
+    /// Checks whether a whitespace between the last written character and
+    /// `item` is required
+    fn needs_space(&self, item: char) -> bool {
+        let Some(last) = self.last else {
+            return false;
+        };
+        if matches!(last, '{' | '}' | ';' | ',' | '>' | '~' | '(' | ':' | '!')
+            || matches!(item, '{' | '}' | ';' | ',' | '>' | '~' | ')' | '!')
+        {
+            return false;
+        }
+        let is_selector = self.is_selector();
+        if item.eq(&':') {
+            is_selector
+        } else if item.eq(&'+') || last.eq(&'+') {
+            !is_selector
+        } else {
+            true
+        }
+    }
+
+    fn push_normal(&mut self, item: char, out: &mut String) {
+        if item.is_whitespace() {
+            self.whitespace = true;
+            self.is_at_rule = false;
+            self.word.clear();
+            return;
+        }
+        if item.eq(&'/') {
+            self.state = State::Slash;
+            return;
+        }
+        self.push_significant(item, out);
+    }
+
+    fn push_significant(&mut self, item: char, out: &mut String) {
+        if self.buffering == Buffering::Value
+            && (self.whitespace || !is_value_char(item))
+            && (item.ne(&'(') || !is_shortened_function(self.options, &self.buffer))
+        {
+            self.flush(out);
+        }
+        if self.semicolon {
+            match item {
+                ';' => {
+                    self.whitespace = false;
+                    return;
+                }
+                '}' => self.semicolon = false,
+                _ => {
+                    self.semicolon = false;
+                    self.emit(';', out);
+                }
+            }
+        }
+        if self.whitespace {
+            self.whitespace = false;
+            if self.needs_space(item) {
+                self.emit(' ', out);
+            }
+        }
+
+        let statement_start = self.statement_start;
+        self.statement_start = false;
+        if statement_start && self.is_declaration_block() {
+            self.is_nested_selector =
+                matches!(item, '&' | '.' | '#' | ':' | '[' | '*' | '>' | '+' | '~');
+        }
+        match item {
+            '"' | '\'' => self.state = State::String(item),
+            '\\' => self.state = State::Escape,
+            '(' => {
+                self.parens
+                    .push(self.is_selector() && self.at_rule.is_empty());
+                if self.buffering == Buffering::Value {
+                    self.buffering = Buffering::Function(self.parens.len());
+                }
+                if self.word.eq_ignore_ascii_case("url") {
+                    self.state = State::UrlStart;
+                }
+            }
+            ')' => {
+                let _ = self.parens.pop();
+            }
+            '{' => self.open_block(),
+            '}' => {
+                if self.blocks.len() > 1 {
+                    let _ = self.blocks.pop();
+                }
+                self.statement_start = true;
+                self.end_declaration();
+            }
+            ';' => {
+                self.at_rule.clear();
+                self.statement_start = true;
+                if self.parens.is_empty() {
+                    self.end_declaration();
+                }
+                if self.is_declaration_block() && self.parens.is_empty() {
+                    self.semicolon = true;
+                    self.word.clear();
+                    return;
+                }
+            }
+            '@' if statement_start => {
+                self.is_at_rule = true;
+                self.at_rule.clear();
+            }
+            ':' if self.is_declaration_block()
+                && self.parens.is_empty()
+                && !self.is_nested_selector =>
+            {
+                self.in_value = true;
+            }
+            _ if self.is_declaration_block() && !self.in_value && !self.is_nested_selector => {
+                self.property.push(item);
+            }
+            _ => {}
+        }
+
+        self.track_word(item);
+        let is_value_start = self.in_value
+            && self.buffering == Buffering::None
+            && self.options.optimizes_values()
+            && !self.property.starts_with("--")
+            && is_value_char(item);
+        if is_value_start {
+            self.buffering = Buffering::Value;
+        }
+        self.emit(item, out);
+        if let Buffering::Function(depth) = self.buffering {
+            if self.parens.len() < depth {
+                self.flush(out);
+            }
+        }
+    }
+
+    fn open_block(&mut self) {
+        let is_rule_list = self.is_at_rule_name(&RULE_LIST_AT_RULES)
+            || self.at_rule.to_ascii_lowercase().ends_with("keyframes");
+        self.blocks.push(if is_rule_list {
+            Block::Rules
+        } else {
+            Block::Declarations
+        });
+        self.at_rule.clear();
+        self.statement_start = true;
+        self.end_declaration();
+    }
+
+    /// Keeps track of the current identifier and at-rule name
+    fn track_word(&mut self, item: char) {
+        if is_identifier(item) {
+            if self.is_at_rule {
+                self.at_rule.push(item);
+            }
+            if self.word.len() < 4 {
+                self.word.push(item);
+            }
+        } else {
+            if item.ne(&'@') {
+                self.is_at_rule = false;
+            }
+            self.word.clear();
+        }
+    }
+
+    fn end_declaration(&mut self) {
+        self.is_nested_selector = false;
+        self.in_value = false;
+        self.property.clear();
+    }
+
+    fn is_at_rule_name(&self, names: &[&str]) -> bool {
+        names
+            .iter()
+            .any(|name| self.at_rule.eq_ignore_ascii_case(name))
+    }
+}
+
+impl Stream for CssMinifier {
+    fn push(&mut self, item: char, out: &mut String) {
+        match self.state {
+            State::Normal => self.push_normal(item, out),
+            State::Slash => {
+                if item.eq(&'*') {
+                    self.state = State::Comment;
+                } else {
+                    self.state = State::Normal;
+                    self.push_significant('/', out);
+                    self.push(item, out);
+                }
+            }
+            State::Comment => {
+                if item.eq(&'*') {
+                    self.state = State::CommentStar;
+                }
+            }
+            State::CommentStar => match item {
+                '/' => {
+                    self.state = State::Normal;
+                    self.whitespace = true;
+                }
+                '*' => {}
+                _ => self.state = State::Comment,
+            },
+            State::String(quote) => {
+                self.emit(item, out);
+                if item.eq(&'\\') {
+                    self.state = State::StringEscape(quote);
+                } else if item.eq(&quote) {
+                    self.state = State::Normal;
+                }
+            }
+            State::StringEscape(quote) => {
+                self.emit(item, out);
+                self.state = State::String(quote);
+            }
+            State::Escape => {
+                self.emit(item, out);
+                self.state = if item.is_ascii_hexdigit() {
+                    State::EscapeHex(1)
+                } else {
+                    State::Normal
+                };
+            }
+            State::EscapeHex(len) => {
+                if item.is_ascii_hexdigit() && len < 6 {
+                    self.emit(item, out);
+                    self.state = State::EscapeHex(len + 1);
+                } else if item.is_whitespace() {
+                    self.emit(' ', out);
+                    self.state = State::Normal;
+                } else {
+                    self.state = State::Normal;
+                    self.push(item, out);
+                }
+            }
+            State::UrlStart => {
+                if !item.is_whitespace() {
+                    self.state = State::Normal;
+                    if item.eq(&'"') || item.eq(&'\'') || item.eq(&')') {
+                        self.push_significant(item, out);
+                    } else {
+                        self.state = State::Url;
+                        self.push(item, out);
+                    }
+                }
+            }
+            State::Url => match item {
+                ')' => {
+                    self.whitespace = false;
+                    self.state = State::Normal;
+                    self.push_significant(item, out);
+                }
+                item if item.is_whitespace() => self.whitespace = true,
+                item => {
+                    if self.whitespace {
+                        self.whitespace = false;
+                        self.emit(' ', out);
+                    }
+                    self.emit(item, out);
+                    if item.eq(&'\\') {
+                        self.state = State::UrlEscape;
+                    }
+                }
+            },
+            State::UrlEscape => {
+                self.emit(item, out);
+                self.state = State::Url;
+            }
+        }
+    }
+
+    fn finish(&mut self, out: &mut String) {
+        if self.state == State::Slash {
+            self.state = State::Normal;
+            self.push_significant('/', out);
+        }
+        self.flush(out);
+        if self.semicolon && !self.is_declaration_block() {
+            self.emit(';', out);
+        }
+        self.semicolon = false;
+        self.whitespace = false;
+    }
+}
+
+/// Checks whether a character can be part of a css identifier
+fn is_identifier(item: char) -> bool {
+    item.is_alphanumeric() || item.eq(&'-') || item.eq(&'_') || !item.is_ascii()
+}
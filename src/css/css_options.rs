@@ -0,0 +1,101 @@
+/// Options for CSS minification
+///
+/// The default options apply the same rules as [`minify`](super::minify),
+/// all value optimizations are opt-in. Every setter consumes and returns the
+/// options, so they can be chained:
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::css::CssOptions;
+///
+/// fn main() {
+///     let options = CssOptions::new()
+///         .shorten_colors(true)
+///         .remove_zero_units(true);
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CssOptions {
+    pub(crate) shorten_colors: bool,
+    pub(crate) remove_zero_units: bool,
+    pub(crate) shorten_numbers: bool,
+    pub(crate) shorten_font_weights: bool,
+}
+
+impl Default for CssOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CssOptions {
+    /// Creates the default options
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            shorten_colors: false,
+            remove_zero_units: false,
+            shorten_numbers: false,
+            shorten_font_weights: false,
+        }
+    }
+
+    /// Creates options with all value optimizations enabled
+    #[must_use]
+    pub const fn all() -> Self {
+        Self {
+            shorten_colors: true,
+            remove_zero_units: true,
+            shorten_numbers: true,
+            shorten_font_weights: true,
+        }
+    }
+
+    /// Write colors in their shortest form (default: `false`)
+    ///
+    /// `#FFFFFF` => `#fff`, `rgb(255, 0, 0)` => `red`
+    #[must_use]
+    pub const fn shorten_colors(mut self, shorten_colors: bool) -> Self {
+        self.shorten_colors = shorten_colors;
+        self
+    }
+
+    /// Remove the unit of zero lengths (default: `false`)
+    ///
+    /// `0px` => `0`. Zeros inside functions like `calc` and percentages,
+    /// times or angles keep their unit.
+    #[must_use]
+    pub const fn remove_zero_units(mut self, remove_zero_units: bool) -> Self {
+        self.remove_zero_units = remove_zero_units;
+        self
+    }
+
+    /// Remove leading and trailing zeros of numbers (default: `false`)
+    ///
+    /// `0.50em` => `.5em`
+    #[must_use]
+    pub const fn shorten_numbers(mut self, shorten_numbers: bool) -> Self {
+        self.shorten_numbers = shorten_numbers;
+        self
+    }
+
+    /// Write font weights as numbers (default: `false`)
+    ///
+    /// `font-weight:bold` => `font-weight:700`, `font-weight:normal` =>
+    /// `font-weight:400`
+    #[must_use]
+    pub const fn shorten_font_weights(mut self, shorten_font_weights: bool) -> Self {
+        self.shorten_font_weights = shorten_font_weights;
+        self
+    }
+}
+
+impl CssOptions {
+    /// Checks whether values are rewritten at all
+    pub(crate) const fn optimizes_values(self) -> bool {
+        self.shorten_colors
+            || self.remove_zero_units
+            || self.shorten_numbers
+            || self.shorten_font_weights
+    }
+}
@@ -0,0 +1,328 @@
+pub use crate::css::css_options::CssOptions;
+use crate::{
+    css::css_minifier::CssMinifier,
+    io::{
+        reader::{Filter, InternalReader},
+        stream::StreamFilter,
+    },
+};
+use std::io::Read;
+
+mod css_minifier;
+mod css_options;
+mod tree;
+mod values;
+
+type CssFilter<I> = StreamFilter<I, CssMinifier>;
+
+/// Reader Implementation for CSS minification
+pub type Reader<R> = InternalReader<CssFilter<Filter<R>>>;
+
+/// Minifies a given String by CSS minification rules
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::css::minify;
+///
+/// fn main() {
+///     let css = r#"
+///         a:hover ,
+///         p  >  b {
+///             color : red ;
+///         }
+///     "#;
+///     let css_minified = minify(css);
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn minify(css: &str) -> String {
+    minify_with_options(css, CssOptions::default())
+}
+
+/// Minifies a given String by CSS minification rules, configured by the
+/// given options
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::css::{minify_with_options, CssOptions};
+///
+/// fn main() {
+///     let css = r#"
+///         a {
+///             color : #FFFFFF ;
+///             margin : 0px  0.5em ;
+///         }
+///     "#;
+///     let options = CssOptions::all();
+///     let css_minified = minify_with_options(css, options);
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn minify_with_options(css: &str, options: CssOptions) -> String {
+    let filtered = css.chars();
+    CssFilter::new(filtered, CssMinifier::with_options(options)).collect()
+}
+
+/// Minifies a given String by CSS minification rules, configured by the
+/// given options, and restructures its rules afterwards
+///
+/// Adjacent rules with identical selectors are merged, overridden
+/// declarations and empty rules are removed and `margin-*` and `padding-*`
+/// longhands are folded into `margin` and `padding`. As this requires the
+/// complete style sheet, there is no [`Read`] based counterpart.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::css::{minify_advanced, CssOptions};
+///
+/// fn main() {
+///     let css = r#"
+///         a { color : red ; margin-top : 0 }
+///         a { color : blue ; margin-right : 0 ; margin-bottom : 0 ; margin-left : 0 }
+///     "#;
+///     let css_minified = minify_advanced(css, CssOptions::default());
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn minify_advanced(css: &str, options: CssOptions) -> String {
+    tree::restructure(&minify_with_options(css, options))
+}
+
+/// Minifies the declarations of a `style` attribute by CSS minification rules
+pub(crate) fn minify_inline(css: &str) -> String {
+    let filtered = css.chars();
+    CssFilter::new(filtered, CssMinifier::inline(CssOptions::default())).collect()
+}
+
+/// Minifies a given Read by CSS minification rules
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use std::fs::File;
+/// use std::io::Read;
+/// use minify::css::minify_from_read;
+///
+/// fn main() {
+///     let mut css_minified = String::new();
+///     let mut file = File::open("tests/files/test.css").expect("file not found");
+///     minify_from_read(file).read_to_string(&mut css_minified);
+/// }
+/// ```
+#[inline]
+pub fn minify_from_read<R: Read>(css: R) -> Reader<R> {
+    minify_from_read_with_options(css, CssOptions::default())
+}
+
+/// Minifies a given Read by CSS minification rules, configured by the given
+/// options
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use std::fs::File;
+/// use std::io::Read;
+/// use minify::css::{minify_from_read_with_options, CssOptions};
+///
+/// fn main() {
+///     let mut css_minified = String::new();
+///     let mut file = File::open("tests/files/test.css").expect("file not found");
+///     let options = CssOptions::new().shorten_colors(true);
+///     minify_from_read_with_options(file, options).read_to_string(&mut css_minified);
+/// }
+/// ```
+#[inline]
+pub fn minify_from_read_with_options<R: Read>(css: R, options: CssOptions) -> Reader<R> {
+    Reader::from_read(css, |chars| {
+        CssFilter::new(chars, CssMinifier::with_options(options))
+    })
+}
+
+#[test]
+fn removal_from_read() {
+    use std::fs::File;
+
+    let file = File::open("tests/files/test.css").expect("file not found");
+    let expected = "body>p,a:hover{color:red;background:url(\"a b.png\") no-repeat}\
+         @media screen and (max-width:100px){p{margin:0 auto}}";
+    let mut actual = String::new();
+    let _ = minify_from_read(file)
+        .read_to_string(&mut actual)
+        .expect("error at read");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn removal_of_comments() {
+    let input = "/* a */ a /* b */ { color : red /* c */ }";
+    let expected = "a{color:red}";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn removal_of_last_semicolon() {
+    let input = "a { color : red ; ; } @import url( b.css ) ;";
+    let expected = "a{color:red}@import url(b.css);";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn keep_whitespace_in_selectors() {
+    let input = ".a  :first-child , .b  * , .c  +  .d { margin : 0 }";
+    let expected = ".a :first-child,.b *,.c+.d{margin:0}";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_whitespace_in_calc() {
+    let input = "a { width : calc( 100%  -  ( 2px  +  3px ) ) }";
+    let expected = "a{width:calc(100% - (2px + 3px))}";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_strings_and_urls() {
+    let input = r#"a::after { content : "  /* b */  ;  " ; background : url( c\ d.png ) }"#;
+    let expected = r#"a::after{content:"  /* b */  ;  ";background:url(c\ d.png)}"#;
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn keep_escapes() {
+    let input = r".\31 0 { color : red }";
+    let expected = r".\31 0{color:red}";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn keep_descendant_combinators_in_selector_functions() {
+    let input = ".a:not( .b :hover ) , :is( a :focus ) { color : red }";
+    let expected = ".a:not(.b :hover),:is(a :focus){color:red}";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn keep_descendant_combinators_in_nested_rules() {
+    let input = "a { color : red ; & :hover { color : blue } .b :is( c :focus ) { margin : 0 } }";
+    let expected = "a{color:red;& :hover{color:blue}.b :is(c :focus){margin:0}}";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn removal_in_nested_blocks() {
+    let input = "@keyframes a { from { opacity : 0 ; } to { opacity : 1 ; } }";
+    let expected = "@keyframes a{from{opacity:0}to{opacity:1}}";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn shorten_colors_with_option() {
+    let input = "#FFFFFF { color : #FFFFFF ; background : #AaBbCc ; border-color : rgb( 255 , 0 , 0 ) #ff000088 rgb(1, 2, 3) ; fill : #808080 }";
+    let expected = "#FFFFFF{color:#fff;background:#abc;border-color:red #f008 #010203;fill:gray}";
+    let options = CssOptions::new().shorten_colors(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn shorten_numbers_with_option() {
+    let input = "a { margin : 0px  0.50em -0.5px 10.0% ; width : calc( 0px + 1em ) ; opacity : 0.0 ; transition : 0s ; --a : 0px }";
+    let expected =
+        "a{margin:0 .5em -.5px 10%;width:calc(0px + 1em);opacity:0;transition:0s;--a:0px}";
+    let options = CssOptions::new()
+        .remove_zero_units(true)
+        .shorten_numbers(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn shorten_font_weights_with_option() {
+    let input = "a { font-weight : bold } b { font-weight : normal ; font : bold 12px/1.5 serif } .bold { content : bold }";
+    let expected =
+        "a{font-weight:700}b{font-weight:400;font:700 12px/1.5 serif}.bold{content:bold}";
+    let options = CssOptions::new().shorten_font_weights(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_values_by_default() {
+    let input = "a { color : #FFFFFF ; margin : 0px 0.5em ; font-weight : bold }";
+    let expected = "a{color:#FFFFFF;margin:0px 0.5em;font-weight:bold}";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn merge_rules_with_identical_selectors() {
+    let input = "a { color : red } a { margin : 0 } b { color : red } a { padding : 0 } c { }";
+    let expected = "a{color:red;margin:0}b{color:red}a{padding:0}";
+    let actual = minify_advanced(input, CssOptions::default());
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn removal_of_overridden_declarations() {
+    let input = "a { color : red ; color : blue ; width : 1px !important ; width : 2px ; display : -webkit-box ; display : flex ; height : 1px ; height : calc( 1em + 1px ) } @media print { b { color : red } b { color : blue } }";
+    let expected = "a{color:blue;width:1px!important;display:-webkit-box;display:flex;height:1px;height:calc(1em + 1px)}@media print{b{color:blue}}";
+    let actual = minify_advanced(input, CssOptions::default());
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn restructure_in_all_rule_list_at_rules() {
+    let input = "@scope ( .a ) { b { color : red } b { color : blue } } @starting-style { c { opacity : 0 ; opacity : 1 } }";
+    let expected = "@scope (.a){b{color:blue}}@starting-style{c{opacity:1}}";
+    let actual = minify_advanced(input, CssOptions::default());
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn fold_shorthand_properties() {
+    let input = "a { margin : 1px ; margin-top : 0 ; margin-right : 1px ; margin-bottom : 0 ; margin-left : 1px ; color : red } b { padding-top : 1px ; padding-right : 2px ; padding-bottom : 3px ; padding-left : inherit }";
+    let expected = "a{margin:0 1px;color:red}b{padding-top:1px;padding-right:2px;padding-bottom:3px;padding-left:inherit}";
+    let actual = minify_advanced(input, CssOptions::default());
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_longhands_overlapped_by_logical_properties() {
+    let input = "a { margin-top : 0 ; margin-block-start : 1px ; margin-right : 0 ; margin-bottom : 0 ; margin-left : 0 } b { padding-inline : 1px ; padding-top : 0 ; padding-right : 0 ; padding-bottom : 0 ; padding-left : 0 ; color : red }";
+    let expected = "a{margin-top:0;margin-block-start:1px;margin-right:0;margin-bottom:0;margin-left:0}b{padding-inline:1px;padding:0;color:red}";
+    let actual = minify_advanced(input, CssOptions::default());
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_other_at_rules() {
+    let input = r#"@import url( a.css ) ; @font-face { font-family : a ; font-family : b } a { content : "}" } a { color : red }"#;
+    let expected =
+        r#"@import url(a.css);@font-face{font-family:a;font-family:b}a{content:"}";color:red}"#;
+    let actual = minify_advanced(input, CssOptions::default());
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,347 @@
+use crate::css::css_minifier::RULE_LIST_AT_RULES;
+use std::mem;
+
+/// Shorthand properties and their longhands in the order of the shorthand
+const SHORTHANDS: [(&str, [&str; 4]); 2] = [
+    (
+        "margin",
+        ["margin-top", "margin-right", "margin-bottom", "margin-left"],
+    ),
+    (
+        "padding",
+        [
+            "padding-top",
+            "padding-right",
+            "padding-bottom",
+            "padding-left",
+        ],
+    ),
+];
+
+/// Values which cannot be combined with others in a shorthand
+const GLOBAL_VALUES: [&str; 5] = ["inherit", "initial", "revert", "revert-layer", "unset"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Declaration {
+    property: String,
+    value: String,
+    important: bool,
+}
+
+impl Declaration {
+    fn parse(declaration: &str) -> Option<Self> {
+        let (property, value) = declaration.split_once(':')?;
+        let suffix = value.len().checked_sub("!important".len());
+        let important = suffix.is_some_and(|suffix| {
+            value
+                .get(suffix..)
+                .is_some_and(|end| end.eq_ignore_ascii_case("!important"))
+        });
+        let value = if important {
+            &value[..value.len() - "!important".len()]
+        } else {
+            value
+        };
+        Some(Self {
+            property: property.to_owned(),
+            value: value.to_owned(),
+            important,
+        })
+    }
+
+    /// Checks whether the declaration sets the given property, custom
+    /// properties are case-sensitive
+    fn is(&self, property: &str) -> bool {
+        if self.property.starts_with("--") {
+            self.property == property
+        } else {
+            self.property.eq_ignore_ascii_case(property)
+        }
+    }
+
+    /// Checks whether this declaration makes an earlier one of the same
+    /// property obsolete. Values with functions or vendor prefixes are
+    /// treated as fallbacks and never override anything.
+    fn overrides(&self, earlier: &Self) -> bool {
+        let is_fallback = self.value.contains('(')
+            || self.value.starts_with('-')
+            || earlier.value.starts_with('-');
+        earlier.is(&self.property) && !is_fallback && (self.important || !earlier.important)
+    }
+
+    fn write(&self, out: &mut String) {
+        out.push_str(&self.property);
+        out.push(':');
+        out.push_str(&self.value);
+        if self.important {
+            out.push_str("!important");
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Rule {
+        selector: String,
+        declarations: Vec<Declaration>,
+    },
+    Block {
+        prelude: String,
+        nodes: Vec<Self>,
+    },
+    Raw(String),
+}
+
+/// Restructures minified css: merges adjacent rules with identical
+/// selectors, removes overridden declarations and empty rules and folds
+/// longhand properties into their shorthand
+pub fn restructure(css: &str) -> String {
+    let mut nodes = parse_nodes(css);
+    optimize(&mut nodes);
+    let mut out = String::with_capacity(css.len());
+    write_nodes(&nodes, &mut out);
+    out
+}
+
+/// Returns the position of the first target character outside of strings,
+/// functions and blocks
+fn find_top_level(input: &str, targets: &[char]) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut quote = None;
+    let mut escaped = false;
+    for (pos, item) in input.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match quote {
+            _ if item.eq(&'\\') => escaped = true,
+            Some(end) => {
+                if item.eq(&end) {
+                    quote = None;
+                }
+            }
+            None => match item {
+                '"' | '\'' => quote = Some(item),
+                _ if depth == 0 && targets.contains(&item) => return Some(pos),
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+fn parse_nodes(input: &str) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut rest = input;
+    while !rest.is_empty() {
+        let Some(pos) = find_top_level(rest, &['{', ';']) else {
+            nodes.push(Node::Raw(rest.to_owned()));
+            break;
+        };
+        if rest[pos..].starts_with(';') {
+            nodes.push(Node::Raw(rest[..=pos].to_owned()));
+            rest = &rest[pos + 1..];
+            continue;
+        }
+        let block = &rest[pos + 1..];
+        let Some(end) = find_top_level(block, &['}']) else {
+            nodes.push(Node::Raw(rest.to_owned()));
+            break;
+        };
+        nodes.push(parse_node(&rest[..pos], &block[..end]));
+        rest = &block[end + 1..];
+    }
+    nodes
+}
+
+fn parse_node(prelude: &str, body: &str) -> Node {
+    let raw = || Node::Raw(format!("{prelude}{{{body}}}"));
+    if let Some(at_rule) = prelude.strip_prefix('@') {
+        let name = at_rule
+            .split(|item: char| !item.is_alphanumeric() && item.ne(&'-'))
+            .next()
+            .unwrap_or_default();
+        if RULE_LIST_AT_RULES
+            .iter()
+            .any(|rule_list| name.eq_ignore_ascii_case(rule_list))
+        {
+            return Node::Block {
+                prelude: prelude.to_owned(),
+                nodes: parse_nodes(body),
+            };
+        }
+        return raw();
+    }
+    if find_top_level(body, &['{']).is_some() {
+        return raw();
+    }
+    parse_declarations(body).map_or_else(raw, |declarations| Node::Rule {
+        selector: prelude.to_owned(),
+        declarations,
+    })
+}
+
+fn parse_declarations(body: &str) -> Option<Vec<Declaration>> {
+    let mut declarations = Vec::new();
+    let mut rest = body;
+    while !rest.is_empty() {
+        let end = find_top_level(rest, &[';']).unwrap_or(rest.len());
+        if end > 0 {
+            declarations.push(Declaration::parse(&rest[..end])?);
+        }
+        rest = rest.get(end + 1..).unwrap_or_default();
+    }
+    Some(declarations)
+}
+
+fn optimize(nodes: &mut Vec<Node>) {
+    let mut merged: Vec<Node> = Vec::with_capacity(nodes.len());
+    for node in mem::take(nodes) {
+        match (merged.last_mut(), node) {
+            (
+                Some(Node::Rule {
+                    selector: previous,
+                    declarations: previous_declarations,
+                }),
+                Node::Rule {
+                    selector,
+                    mut declarations,
+                },
+            ) if *previous == selector => previous_declarations.append(&mut declarations),
+            (_, node) => merged.push(node),
+        }
+    }
+    for node in &mut merged {
+        match node {
+            Node::Rule { declarations, .. } => {
+                remove_overridden(declarations);
+                for (shorthand, longhands) in &SHORTHANDS {
+                    fold_shorthand(declarations, shorthand, longhands);
+                }
+                remove_overridden(declarations);
+            }
+            Node::Block { nodes, .. } => optimize(nodes),
+            Node::Raw(_) => {}
+        }
+    }
+    merged
+        .retain(|node| !matches!(node, Node::Rule { declarations, .. } if declarations.is_empty()));
+    *nodes = merged;
+}
+
+fn remove_overridden(declarations: &mut Vec<Declaration>) {
+    let mut kept = Vec::with_capacity(declarations.len());
+    for (pos, declaration) in declarations.iter().enumerate() {
+        let is_overridden = declarations[pos + 1..]
+            .iter()
+            .any(|later| later.overrides(declaration))
+            || declarations[..pos].iter().any(|earlier| {
+                earlier.important && !declaration.important && declaration.is(&earlier.property)
+            });
+        if !is_overridden {
+            kept.push(declaration.clone());
+        }
+    }
+    *declarations = kept;
+}
+
+/// Replaces the four longhands by their shorthand:
+/// `margin-top:0;margin-right:1px;margin-bottom:0;margin-left:1px` =>
+/// `margin:0 1px`
+///
+/// The shorthand takes the place of the first longhand. Longhands with
+/// another property of the family between them, like `margin-block-start`,
+/// are kept, as that property may set the same side.
+fn fold_shorthand(declarations: &mut Vec<Declaration>, shorthand: &str, longhands: &[&str; 4]) {
+    let positions: Option<Vec<usize>> = longhands
+        .iter()
+        .map(|longhand| {
+            declarations
+                .iter()
+                .rposition(|declaration| declaration.is(longhand))
+        })
+        .collect();
+    let Some(positions) = positions else {
+        return;
+    };
+    let first = positions.iter().copied().min().unwrap_or_default();
+    let last = positions.iter().copied().max().unwrap_or_default();
+    let important = declarations[first].important;
+    let is_foldable = positions.iter().all(|pos| {
+        let value = &declarations[*pos].value;
+        declarations[*pos].important == important
+            && !value.contains("var(")
+            && !GLOBAL_VALUES
+                .iter()
+                .any(|global| value.eq_ignore_ascii_case(global))
+    }) && !declarations[first..]
+        .iter()
+        .any(|declaration| declaration.is(shorthand));
+    let family = format!("{shorthand}-");
+    let is_overlapped = (first..=last).any(|pos| {
+        !positions.contains(&pos)
+            && declarations[pos]
+                .property
+                .get(..family.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&family))
+    });
+    if !is_foldable || is_overlapped {
+        return;
+    }
+
+    let values: Vec<&str> = positions
+        .iter()
+        .map(|pos| declarations[*pos].value.as_str())
+        .collect();
+    let len = if values[1] != values[3] {
+        4
+    } else if values[0] != values[2] {
+        3
+    } else if values[0] != values[1] {
+        2
+    } else {
+        1
+    };
+    let folded = Declaration {
+        property: shorthand.to_owned(),
+        value: values[..len].join(" "),
+        important,
+    };
+    let mut pos = 0;
+    declarations.retain(|_| {
+        pos += 1;
+        !positions.contains(&(pos - 1))
+    });
+    declarations.insert(first, folded);
+}
+
+fn write_nodes(nodes: &[Node], out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Rule {
+                selector,
+                declarations,
+            } => {
+                out.push_str(selector);
+                out.push('{');
+                for (pos, declaration) in declarations.iter().enumerate() {
+                    if pos > 0 {
+                        out.push(';');
+                    }
+                    declaration.write(out);
+                }
+                out.push('}');
+            }
+            Node::Block { prelude, nodes } => {
+                out.push_str(prelude);
+                out.push('{');
+                write_nodes(nodes, out);
+                out.push('}');
+            }
+            Node::Raw(raw) => out.push_str(raw),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::css::css_options::CssOptions;
+use std::fmt::Write;
+
+/// Units of lengths which may be omitted for a zero length
+const LENGTH_UNITS: [&str; 15] = [
+    "ch", "cm", "em", "ex", "in", "mm", "pc", "pt", "px", "q", "rem", "vh", "vmax", "vmin", "vw",
+];
+
+/// Colors whose name is shorter than their shortest hex notation
+const COLOR_NAMES: [(&str, &str); 31] = [
+    ("#000080", "navy"),
+    ("#008000", "green"),
+    ("#008080", "teal"),
+    ("#4b0082", "indigo"),
+    ("#800000", "maroon"),
+    ("#800080", "purple"),
+    ("#808000", "olive"),
+    ("#808080", "gray"),
+    ("#a0522d", "sienna"),
+    ("#a52a2a", "brown"),
+    ("#c0c0c0", "silver"),
+    ("#cd853f", "peru"),
+    ("#d2b48c", "tan"),
+    ("#da70d6", "orchid"),
+    ("#dda0dd", "plum"),
+    ("#ee82ee", "violet"),
+    ("#f00", "red"),
+    ("#f0e68c", "khaki"),
+    ("#f0ffff", "azure"),
+    ("#f5deb3", "wheat"),
+    ("#f5f5dc", "beige"),
+    ("#fa8072", "salmon"),
+    ("#faf0e6", "linen"),
+    ("#ff6347", "tomato"),
+    ("#ff7f50", "coral"),
+    ("#ffa500", "orange"),
+    ("#ffc0cb", "pink"),
+    ("#ffd700", "gold"),
+    ("#ffe4c4", "bisque"),
+    ("#fffafa", "snow"),
+    ("#fffff0", "ivory"),
+];
+
+/// Checks whether a character can be part of a value which may be shortened
+pub fn is_value_char(item: char) -> bool {
+    item.is_alphanumeric() || matches!(item, '-' | '_' | '.' | '#' | '%') || !item.is_ascii()
+}
+
+/// Returns the shortest form of a single value of the given property.
+///
+/// `is_nested` is set for values inside of functions like `calc`, where a
+/// zero length keeps its unit.
+pub fn shorten_value(options: CssOptions, property: &str, is_nested: bool, value: &str) -> String {
+    if let Some(hex) = value.strip_prefix('#') {
+        if options.shorten_colors {
+            if let Some(color) = shorten_hex(hex) {
+                return color;
+            }
+        }
+        return value.to_owned();
+    }
+    if options.shorten_font_weights {
+        let is_font_weight = property.eq_ignore_ascii_case("font-weight");
+        if value.eq_ignore_ascii_case("bold")
+            && (is_font_weight || property.eq_ignore_ascii_case("font"))
+        {
+            return "700".to_owned();
+        }
+        if value.eq_ignore_ascii_case("normal") && is_font_weight {
+            return "400".to_owned();
+        }
+    }
+    let remove_unit =
+        options.remove_zero_units && !is_nested && !property.eq_ignore_ascii_case("flex");
+    shorten_number(value, remove_unit, options.shorten_numbers).unwrap_or_else(|| value.to_owned())
+}
+
+/// Returns the shortest form of a function call like `rgb(255, 0, 0)`
+pub fn shorten_function(options: CssOptions, function: &str) -> String {
+    if options.shorten_colors {
+        if let Some(color) = shorten_rgb(function) {
+            return color;
+        }
+    }
+    function.to_owned()
+}
+
+/// Checks whether a function call may be shortened as a whole
+pub fn is_shortened_function(options: CssOptions, name: &str) -> bool {
+    options.shorten_colors && name.eq_ignore_ascii_case("rgb")
+}
+
+fn shorten_rgb(function: &str) -> Option<String> {
+    let arguments = function
+        .get(..4)
+        .filter(|name| name.eq_ignore_ascii_case("rgb("))
+        .and_then(|_| function[4..].strip_suffix(')'))?;
+    let mut hex = String::with_capacity(6);
+    let mut count = 0;
+    for argument in arguments
+        .split(|item: char| item.eq(&',') || item.is_whitespace())
+        .filter(|argument| !argument.is_empty())
+    {
+        if count == 3 || !argument.chars().all(|item| item.is_ascii_digit()) {
+            return None;
+        }
+        let channel: u8 = argument.parse().ok()?;
+        let _ = write!(hex, "{channel:02x}");
+        count += 1;
+    }
+    if count == 3 {
+        shorten_hex(&hex)
+    } else {
+        None
+    }
+}
+
+/// Shortens a hex color given without its `#`: `FFFFFF` => `#fff`
+fn shorten_hex(hex: &str) -> Option<String> {
+    if !matches!(hex.len(), 3 | 4 | 6 | 8) || !hex.chars().all(|item| item.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hex = hex.to_ascii_lowercase();
+    let bytes = hex.as_bytes();
+    let is_shortenable = hex.len() > 4 && bytes.chunks(2).all(|pair| pair[0] == pair[1]);
+    let mut color = String::with_capacity(hex.len() + 1);
+    color.push('#');
+    if is_shortenable {
+        color.extend(bytes.chunks(2).map(|pair| char::from(pair[0])));
+    } else {
+        color.push_str(&hex);
+    }
+    let name = COLOR_NAMES
+        .iter()
+        .find(|(hex, _)| hex == &color)
+        .map(|(_, name)| (*name).to_owned());
+    Some(name.unwrap_or(color))
+}
+
+/// Shortens a number with an optional unit: `0.50em` => `.5em`
+fn shorten_number(value: &str, remove_unit: bool, shorten: bool) -> Option<String> {
+    let (sign, rest) = value
+        .strip_prefix(['-', '+'])
+        .map_or(("", value), |rest| (&value[..1], rest));
+    let end = rest
+        .find(|item: char| !item.is_ascii_digit() && item.ne(&'.'))
+        .unwrap_or(rest.len());
+    let (number, unit) = rest.split_at(end);
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let is_number = !(fraction.contains('.') || integer.is_empty() && fraction.is_empty())
+        && (unit.eq("%") || unit.chars().all(|item| item.is_ascii_alphabetic()));
+    if !is_number {
+        return None;
+    }
+
+    let is_zero = integer
+        .chars()
+        .chain(fraction.chars())
+        .all(|item| item.eq(&'0'));
+    if is_zero {
+        let is_length = LENGTH_UNITS
+            .iter()
+            .any(|length| unit.eq_ignore_ascii_case(length));
+        if remove_unit && is_length {
+            return Some("0".to_owned());
+        }
+        return shorten.then(|| format!("0{unit}"));
+    }
+    if !shorten {
+        return None;
+    }
+    let integer = integer.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+    let mut shortened = String::with_capacity(value.len());
+    shortened.push_str(sign);
+    shortened.push_str(integer);
+    if !fraction.is_empty() {
+        shortened.push('.');
+        shortened.push_str(fraction);
+    }
+    shortened.push_str(unit);
+    Some(shortened)
+}
@@ -0,0 +1,341 @@
+pub use crate::io::multi_filter::Action;
+use crate::io::{
+    multi_filter::MultiFilter,
+    reader::{Filter, InternalReader},
+};
+use std::io::Read;
+
+/// Default number of characters following the current one in a [`Window`]
+pub const LOOKAHEAD: usize = 5;
+
+/// The character a [`Minifier`] decides on, together with the characters
+/// following it in the input
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Window<'a> {
+    current: char,
+    following: &'a [Option<char>],
+}
+
+impl<'a> Window<'a> {
+    /// Returns the character to decide on
+    #[must_use]
+    pub const fn current(&self) -> char {
+        self.current
+    }
+
+    /// Returns the character `offset` positions after the current one
+    ///
+    /// Returns `None` at the end of the input and for an `offset` of `0` or
+    /// larger than the lookahead of the minifier.
+    #[must_use]
+    pub fn peek(&self, offset: usize) -> Option<char> {
+        let pos = offset.checked_sub(1)?;
+        self.following.get(pos).copied().flatten()
+    }
+
+    /// Returns the characters following the current one, which are `None`
+    /// after the end of the input
+    #[must_use]
+    pub const fn following(&self) -> &'a [Option<char>] {
+        self.following
+    }
+}
+
+/// A minifier for a custom format, which decides for every character of
+/// the input whether it is kept, dropped or replaced
+///
+/// The minifier is called once for every character in order, so it can
+/// track the state of the format, like being inside of a string, in `self`.
+/// It sees the `N` characters following the current one, [`LOOKAHEAD`] by
+/// default, and may implement the trait for a larger `N` to look further
+/// ahead.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::custom::{minify, Action, Minifier, Window};
+///
+/// /// Collapses whitespaces into a single space
+/// struct Whitespace;
+///
+/// impl Minifier for Whitespace {
+///     fn action(&mut self, window: &Window) -> Action {
+///         let is_space = |item: char| item.is_whitespace();
+///         if !is_space(window.current()) {
+///             Action::Keep
+///         } else if window.peek(1).is_some_and(is_space) {
+///             Action::Drop
+///         } else {
+///             Action::Replace(" ".into())
+///         }
+///     }
+/// }
+///
+/// /// Removes the word `unneeded`, which needs a lookahead of seven
+/// #[derive(Default)]
+/// struct Unneeded {
+///     left: usize,
+/// }
+///
+/// impl Minifier<7> for Unneeded {
+///     fn keep(&mut self, window: &Window) -> bool {
+///         let following = window.following().iter().flatten().copied();
+///         if window.current() == 'u' && following.eq("nneeded".chars()) {
+///             self.left = "unneeded".len();
+///         }
+///         if self.left == 0 {
+///             return true;
+///         }
+///         self.left -= 1;
+///         false
+///     }
+/// }
+///
+/// fn main() {
+///     let minified = minify("a  \n  b\nc", Whitespace);
+///     assert_eq!(minified, "a b c");
+///     let minified = minify("a unneeded b", Unneeded::default());
+///     assert_eq!(minified, "a  b");
+/// }
+/// ```
+pub trait Minifier<const N: usize = LOOKAHEAD> {
+    /// Decides whether the current character of the window is kept, which
+    /// is enough for minifiers that only drop characters
+    ///
+    /// Keeps every character by default.
+    fn keep(&mut self, window: &Window) -> bool {
+        let _ = window;
+        true
+    }
+
+    /// Decides what happens with the current character of the window
+    ///
+    /// Asks [`Minifier::keep`] by default, minifiers which replace
+    /// characters implement this instead.
+    fn action(&mut self, window: &Window) -> Action {
+        self.keep(window).into()
+    }
+}
+
+/// Predicate of the lookahead filters, with the current character followed
+/// by the lookahead
+pub(crate) type ActionMethod<M> = fn(&mut M, char, &[Option<char>]) -> Action;
+
+/// Predicate of the lookahead filters which asks the [`Minifier`]
+#[inline]
+pub(crate) fn action<M: Minifier<N>, const N: usize>(
+    minifier: &mut M,
+    item: char,
+    following: &[Option<char>],
+) -> Action {
+    minifier.action(&Window {
+        current: item,
+        following,
+    })
+}
+
+type CustomFilter<I, M, const N: usize> = MultiFilter<I, ActionMethod<M>, M, N>;
+
+/// Reader Implementation for minification by a [`Minifier`] with a
+/// lookahead of `N`
+pub type Reader<R, M, const N: usize = LOOKAHEAD> = InternalReader<CustomFilter<Filter<R>, M, N>>;
+
+/// Minifies a given String by the rules of the given [`Minifier`]
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::custom::{minify, Minifier, Window};
+///
+/// /// Removes everything but digits
+/// struct Digits;
+///
+/// impl Minifier for Digits {
+///     fn keep(&mut self, window: &Window) -> bool {
+///         window.current().is_ascii_digit()
+///     }
+/// }
+///
+/// fn main() {
+///     let minified = minify("a1b2", Digits);
+///     assert_eq!(minified, "12");
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn minify<M: Minifier<N>, const N: usize>(input: &str, minifier: M) -> String {
+    let filtered = input.chars();
+    CustomFilter::<_, _, N>::with_minifier(filtered, action::<M, N>, minifier).collect()
+}
+
+/// Minifies a given String by the rules of the [`Minifier`] `M`, which uses
+/// the default lookahead
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::custom::{minify_str, Minifier, Window};
+///
+/// /// Removes everything but digits
+/// #[derive(Default)]
+/// struct Digits;
+///
+/// impl Minifier for Digits {
+///     fn keep(&mut self, window: &Window) -> bool {
+///         window.current().is_ascii_digit()
+///     }
+/// }
+///
+/// fn main() {
+///     let minified = minify_str::<Digits>("a1b2");
+///     assert_eq!(minified, "12");
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn minify_str<M: Minifier + Default>(input: &str) -> String {
+    minify(input, M::default())
+}
+
+/// Minifies a given Read by the rules of the given [`Minifier`]
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use std::io::Read;
+/// use minify::custom::{minify_from_read, Minifier, Window};
+///
+/// /// Removes everything but digits
+/// struct Digits;
+///
+/// impl Minifier for Digits {
+///     fn keep(&mut self, window: &Window) -> bool {
+///         window.current().is_ascii_digit()
+///     }
+/// }
+///
+/// fn main() {
+///     let input: &[u8] = b"a1b2";
+///     let mut minified = String::new();
+///     minify_from_read(input, Digits).read_to_string(&mut minified);
+/// }
+/// ```
+#[inline]
+pub fn minify_from_read<R: Read, M: Minifier<N>, const N: usize>(
+    input: R,
+    minifier: M,
+) -> Reader<R, M, N> {
+    Reader::from_read(input, |chars| {
+        CustomFilter::with_minifier(chars, action::<M, N>, minifier)
+    })
+}
+
+/// Keeps a character only if it differs from the next one
+#[cfg(test)]
+#[derive(Debug, Default)]
+struct Deduplicate;
+
+#[cfg(test)]
+impl Minifier for Deduplicate {
+    fn keep(&mut self, window: &Window) -> bool {
+        window.peek(1) != Some(window.current())
+    }
+}
+
+/// Replaces tabs by two spaces
+#[cfg(test)]
+#[derive(Debug, Default)]
+struct Untabify;
+
+#[cfg(test)]
+impl Minifier for Untabify {
+    fn action(&mut self, window: &Window) -> Action {
+        if window.current() == '\t' {
+            Action::Replace("  ".into())
+        } else {
+            Action::Keep
+        }
+    }
+}
+
+/// Removes whitespaces outside of `<![CDATA[...]]>` sections, which needs a
+/// lookahead longer than the default one
+#[cfg(test)]
+#[derive(Debug, Default)]
+struct Cdata {
+    is_cdata: bool,
+}
+
+#[cfg(test)]
+impl Minifier<8> for Cdata {
+    fn action(&mut self, window: &Window) -> Action {
+        let starts_with = |text: &str| {
+            let following = window.following().iter().flatten().copied();
+            following.take(text.len()).eq(text.chars())
+        };
+        match window.current() {
+            '<' if starts_with("![CDATA[") => self.is_cdata = true,
+            ']' if starts_with("]>") => self.is_cdata = false,
+            item if item.is_whitespace() => return self.is_cdata.into(),
+            _ => {}
+        }
+        Action::Keep
+    }
+}
+
+#[test]
+fn minify_with_custom_minifier() {
+    let input = "aaabccdeeeeeeef";
+    let expected = "abcdef";
+    let actual = minify_str::<Deduplicate>(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn replace_with_custom_minifier() {
+    let input = "\ta\t\tb";
+    let expected = "  a    b";
+    let actual = minify_str::<Untabify>(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn minify_from_read_with_custom_minifier() {
+    let input = "aaabccdeeeeeeef";
+    let expected = "abcdef";
+    let mut actual = String::new();
+    let _ = minify_from_read(input.as_bytes(), Deduplicate)
+        .read_to_string(&mut actual)
+        .expect("error at read");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn minify_with_longer_lookahead() {
+    let input = "<a> <![CDATA[ b  c ]]> </a>\n";
+    let expected = "<a><![CDATA[ b  c ]]></a>";
+    assert_eq!(minify(input, Cdata::default()), expected);
+    let mut actual = String::new();
+    let _ = minify_from_read(input.as_bytes(), Cdata::default())
+        .read_to_string(&mut actual)
+        .expect("error at read");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn window_with_lookahead() {
+    let window = Window {
+        current: 'a',
+        following: &[Some('b'), Some('c'), None, None, None],
+    };
+    assert_eq!(window.current(), 'a');
+    assert_eq!(window.peek(0), None);
+    assert_eq!(window.peek(1), Some('b'));
+    assert_eq!(window.peek(2), Some('c'));
+    assert_eq!(window.peek(3), None);
+    assert_eq!(window.peek(LOOKAHEAD + 1), None);
+}
@@ -1,11 +1,115 @@
-#[derive(Debug, Copy, Clone)]
+use crate::{
+    css,
+    html::{
+        html_options::HtmlOptions,
+        optional_tags::OptionalTags,
+        tokenizer::{Attribute, Tag, Token, Tokenizer},
+    },
+    io::stream::Stream,
+    js, json,
+};
+use std::mem;
+
+/// Boolean attributes and the elements they apply to, an empty list applies
+/// to all elements
+const BOOLEAN_ATTRIBUTES: [(&str, &[&str]); 25] = [
+    ("allowfullscreen", &["iframe"]),
+    ("async", &["script"]),
+    ("autofocus", &[]),
+    ("autoplay", &["audio", "video"]),
+    ("checked", &["input"]),
+    ("controls", &["audio", "video"]),
+    ("default", &["track"]),
+    ("defer", &["script"]),
+    (
+        "disabled",
+        &[
+            "button", "fieldset", "input", "link", "optgroup", "option", "select", "textarea",
+        ],
+    ),
+    ("formnovalidate", &["button", "input"]),
+    ("hidden", &[]),
+    ("inert", &[]),
+    ("ismap", &["img"]),
+    ("itemscope", &[]),
+    ("loop", &["audio", "video"]),
+    ("multiple", &["input", "select"]),
+    ("muted", &["audio", "video"]),
+    ("nomodule", &["script"]),
+    ("novalidate", &["form"]),
+    ("open", &["details", "dialog"]),
+    ("playsinline", &["video"]),
+    ("readonly", &["input", "textarea"]),
+    ("required", &["input", "select", "textarea"]),
+    ("reversed", &["ol"]),
+    ("selected", &["option"]),
+];
+
+/// Attributes which can be removed as they only repeat the default value, as
+/// element, attribute and value
+const DEFAULT_ATTRIBUTES: [(&str, &str, &str); 5] = [
+    ("form", "method", "get"),
+    ("input", "type", "text"),
+    ("link", "type", "text/css"),
+    ("script", "type", "text/javascript"),
+    ("style", "type", "text/css"),
+];
+
+/// Types of `script` elements containing javascript, next to an empty type
+const JS_TYPES: [&str; 16] = [
+    "application/ecmascript",
+    "application/javascript",
+    "application/x-ecmascript",
+    "application/x-javascript",
+    "module",
+    "text/ecmascript",
+    "text/javascript",
+    "text/javascript1.0",
+    "text/javascript1.1",
+    "text/javascript1.2",
+    "text/javascript1.3",
+    "text/javascript1.4",
+    "text/javascript1.5",
+    "text/jscript",
+    "text/x-ecmascript",
+    "text/x-javascript",
+];
+
+/// Types of `script` elements containing json
+const JSON_TYPES: [&str; 4] = [
+    "application/json",
+    "application/ld+json",
+    "importmap",
+    "speculationrules",
+];
+
+/// Language of the raw text in the last opened raw text element
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RawText {
+    Other,
+    Css,
+    Js,
+    Json,
+}
+
+/// Whitespace which is written once the next content is known
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Whitespace {
+    None,
+    Space,
+    LineBreak,
+}
+
+#[derive(Debug, Clone)]
 pub struct HtmlMinifier {
+    pub options: HtmlOptions,
+    pub tokenizer: Tokenizer,
+    pub tokens: Vec<Token>,
+    pub optional_tags: OptionalTags,
     pub begin: bool,
-    pub keep_removing: u8,
-    pub last_was_tag_start: bool,
-    pub last_was_tag_end: bool,
-    pub is_comment: bool,
-    pub is_pre: bool,
+    pub whitespace: Whitespace,
+    pub pre_depth: usize,
+    pub raw_text: RawText,
 }
 
 impl Default for HtmlMinifier {
@@ -16,172 +120,339 @@ impl Default for HtmlMinifier {
 
 impl HtmlMinifier {
     pub const fn new() -> Self {
+        Self::with_options(HtmlOptions::new())
+    }
+
+    pub const fn with_options(options: HtmlOptions) -> Self {
         Self {
+            options,
+            tokenizer: Tokenizer::new(),
+            tokens: Vec::new(),
+            optional_tags: OptionalTags::new(),
             begin: true,
-            keep_removing: 0,
-            last_was_tag_start: false,
-            last_was_tag_end: false,
-            is_comment: false,
-            is_pre: false,
+            whitespace: Whitespace::None,
+            pre_depth: 0,
+            raw_text: RawText::Other,
         }
     }
-}
 
-#[inline]
-pub fn keep_element(
-    minifier: &mut HtmlMinifier,
-    item1: char,
-    item2: Option<char>,
-    item3: Option<char>,
-    item4: Option<char>,
-    item5: Option<char>,
-    item6: Option<char>,
-) -> bool {
-    let remove_element = item1.is_ascii_control()
-        || is_comment(minifier, item1, item2, item3, item4, item5, item6)
-        || is_pre(minifier, item1, item2, item3, item4)
-        || is_whitespace_after_tag(minifier, item1, item2)
-        || is_whitespace_before_tag_or_whitespace_or_control(minifier, item1, item2);
-    if !remove_element {
-        minifier.begin = false;
+    fn handle_tokens(&mut self, out: &mut String) {
+        let mut tokens = mem::take(&mut self.tokens);
+        if self.options.omit_optional_tags {
+            tokens = self.optional_tags.filter(self.options, tokens);
+        }
+        for token in tokens {
+            self.handle_token(token, out);
+        }
+    }
+
+    fn handle_token(&mut self, token: Token, out: &mut String) {
+        match token {
+            Token::Text(text) if self.pre_depth > 0 => {
+                self.write_whitespace(out);
+                out.push_str(&text);
+            }
+            Token::Text(text) => self.write_text(&text, out),
+            Token::RawText(text) => {
+                self.write_whitespace(out);
+                match self.raw_text {
+                    RawText::Css if self.options.minify_css => out.push_str(&css::minify(&text)),
+                    RawText::Js if self.options.minify_js => {
+                        let minified = js::minify(&text);
+                        if ends_script_early(&text, &minified) {
+                            out.push_str(&text);
+                        } else {
+                            out.push_str(&minified);
+                        }
+                    }
+                    RawText::Json if self.options.minify_js => {
+                        out.push_str(&json::minify(&text));
+                    }
+                    _ => out.push_str(&text),
+                }
+            }
+            Token::StartTag(tag) => {
+                self.write_whitespace(out);
+                write_tag(self.options, &tag, out);
+                self.raw_text = raw_text_of(&tag);
+                if tag.is("pre") && !tag.self_closing {
+                    self.pre_depth += 1;
+                }
+            }
+            Token::EndTag(name) => {
+                self.write_whitespace(out);
+                out.push_str("</");
+                out.push_str(&name);
+                out.push('>');
+                if name.eq_ignore_ascii_case("pre") {
+                    self.pre_depth = self.pre_depth.saturating_sub(1);
+                }
+            }
+            Token::Comment(comment) => self.write_comment(&comment, out),
+            Token::Doctype(doctype) => {
+                self.write_whitespace(out);
+                out.push_str("<!");
+                for (pos, part) in doctype.split_ascii_whitespace().enumerate() {
+                    if pos > 0 {
+                        out.push(' ');
+                    }
+                    out.push_str(part);
+                }
+                out.push('>');
+            }
+            Token::CData(data) => {
+                self.write_whitespace(out);
+                out.push_str("<![CDATA[");
+                out.push_str(&data);
+                out.push_str("]]>");
+            }
+            Token::Declaration(declaration) => {
+                self.write_whitespace(out);
+                out.push('<');
+                out.push_str(&declaration);
+                out.push('>');
+            }
+        }
     }
-    if minifier.is_pre {
-        return true;
+
+    fn write_comment(&mut self, comment: &str, out: &mut String) {
+        if self.pre_depth > 0 {
+            self.write_whitespace(out);
+            out.push_str("<!--");
+            out.push_str(comment);
+            out.push_str("-->");
+            return;
+        }
+        if self.options.keeps_comment(comment) {
+            self.write_whitespace(out);
+            out.push_str("<!--");
+            write_collapsed_all(self.options, comment, out);
+            out.push_str("-->");
+        } else {
+            self.whitespace = Whitespace::None;
+        }
     }
-    !remove_element
-}
-
-#[inline]
-pub fn is_comment(
-    minifier: &mut HtmlMinifier,
-    item1: char,
-    item2: Option<char>,
-    item3: Option<char>,
-    item4: Option<char>,
-    item5: Option<char>,
-    item6: Option<char>,
-) -> bool {
-    if minifier.keep_removing > 0 {
-        minifier.keep_removing -= 1;
-        return true;
+
+    fn write_text(&mut self, text: &str, out: &mut String) {
+        let len = out.len();
+        write_collapsed(self.options, text, &mut self.whitespace, self.begin, out);
+        self.begin = self.begin && out.len() == len;
     }
-    if equals_comment_start(item1, item2, item3, item4, item5, item6) {
-        minifier.is_comment = true;
+
+    fn write_whitespace(&mut self, out: &mut String) {
+        write_pending(&mut self.whitespace, self.begin, out);
+        self.begin = false;
     }
-    if minifier.is_comment {
-        if equals_comment_end(item1, item2, item3) {
-            minifier.is_comment = false;
-            minifier.keep_removing = 2;
+}
+
+impl Stream for HtmlMinifier {
+    fn push(&mut self, item: char, out: &mut String) {
+        self.tokenizer.push(item, &mut self.tokens);
+        if !self.tokens.is_empty() {
+            self.handle_tokens(out);
+        }
+    }
+
+    fn finish(&mut self, out: &mut String) {
+        self.tokenizer.finish(&mut self.tokens);
+        self.handle_tokens(out);
+        if self.options.omit_optional_tags {
+            for token in self.optional_tags.finish() {
+                self.handle_token(token, out);
+            }
+        }
+        if self.whitespace == Whitespace::LineBreak {
+            self.write_whitespace(out);
         }
-        return true;
     }
-    false
 }
 
-#[inline]
-pub fn is_pre(
-    minifier: &mut HtmlMinifier,
-    item1: char,
-    item2: Option<char>,
-    item3: Option<char>,
-    item4: Option<char>,
-) -> bool {
-    if minifier.last_was_tag_start {
-        if minifier.is_pre && equals_pre_end(item1, item2, item3, item4) {
-            minifier.is_pre = false;
+/// Writes text, removing control characters and collapsing whitespaces.
+///
+/// Whitespaces at the end of the text are not written but stored in
+/// `whitespace`, as they depend on what follows.
+fn write_collapsed(
+    options: HtmlOptions,
+    text: &str,
+    whitespace: &mut Whitespace,
+    begin: bool,
+    out: &mut String,
+) {
+    let mut begin = begin;
+    for item in text.chars() {
+        if options.collapse_whitespace {
+            if item.eq(&'\n') && options.preserve_line_breaks {
+                *whitespace = Whitespace::LineBreak;
+                continue;
+            }
+            if item.eq(&' ') {
+                if *whitespace == Whitespace::None {
+                    *whitespace = Whitespace::Space;
+                }
+                continue;
+            }
+            if item.is_ascii_whitespace() {
+                continue;
+            }
         }
-        if equals_pre_start(item1, item2, item3) {
-            minifier.is_pre = true;
+        if item.is_ascii_control()
+            && options.remove_control_characters
+            && !item.is_ascii_whitespace()
+        {
+            continue;
         }
+        write_pending(whitespace, begin, out);
+        begin = false;
+        out.push(item);
     }
-    false
-}
-
-#[inline]
-pub fn equals_comment_start(
-    item1: char,
-    item2: Option<char>,
-    item3: Option<char>,
-    item4: Option<char>,
-    item5: Option<char>,
-    item6: Option<char>,
-) -> bool {
-    item1.eq(&' ')
-        && item2.eq(&Some('<'))
-        && item3.eq(&Some('!'))
-        && item4.eq(&Some('-'))
-        && item5.eq(&Some('-'))
-        && item6.ne(&Some('['))
-        || item1.eq(&'<')
-            && item2.eq(&Some('!'))
-            && item3.eq(&Some('-'))
-            && item4.eq(&Some('-'))
-            && item5.ne(&Some('['))
-}
-
-#[inline]
-pub fn equals_comment_end(item1: char, item2: Option<char>, item3: Option<char>) -> bool {
-    item1.eq(&'-') && item2.eq(&Some('-')) && item3.eq(&Some('>'))
-}
-
-#[inline]
-pub fn equals_pre_start(
-    item1: char,
-    item2: Option<char>,
-    item3: Option<char>,
-) -> bool {
-    item1.eq(&'p')
-        && item2.eq(&Some('r'))
-        && item3.eq(&Some('e'))
-}
-
-#[inline]
-pub fn equals_pre_end(
-    item1: char,
-    item2: Option<char>,
-    item3: Option<char>,
-    item4: Option<char>,
-) -> bool {
-    item1.eq(&'/')
-        && item2.eq(&Some('p'))
-        && item3.eq(&Some('r'))
-        && item4.eq(&Some('e'))
-}
-
-#[inline]
-pub fn is_whitespace_before_tag_or_whitespace_or_control(
-    minifier: &mut HtmlMinifier,
-    item1: char,
-    item2: Option<char>,
-) -> bool {
-    if item1.is_whitespace() {
-        return match item2 {
-            Some('<') if minifier.begin => true,
-            Some('>' | ' ') => true,
-            Some(item) if item.is_ascii_control() => true,
-            _ => false,
-        };
+}
+
+/// Writes text like [`write_collapsed`] including whitespaces at its end
+fn write_collapsed_all(options: HtmlOptions, text: &str, out: &mut String) {
+    let mut whitespace = Whitespace::None;
+    write_collapsed(options, text, &mut whitespace, false, out);
+    write_pending(&mut whitespace, false, out);
+}
+
+fn write_pending(whitespace: &mut Whitespace, begin: bool, out: &mut String) {
+    if !begin {
+        match whitespace {
+            Whitespace::None => {}
+            Whitespace::Space => out.push(' '),
+            Whitespace::LineBreak => out.push('\n'),
+        }
     }
-    false
+    *whitespace = Whitespace::None;
 }
 
-#[inline]
-pub fn is_whitespace_after_tag(
-    minifier: &mut HtmlMinifier,
-    item1: char,
-    item2: Option<char>,
-) -> bool {
-    if minifier.last_was_tag_start && item1.is_whitespace() {
-        return true;
+fn write_tag(options: HtmlOptions, tag: &Tag, out: &mut String) {
+    out.push('<');
+    out.push_str(&tag.name);
+    let mut unquoted_end = false;
+    for attribute in &tag.attributes {
+        if options.remove_default_attributes && is_default_attribute(tag, attribute) {
+            continue;
+        }
+        out.push(' ');
+        out.push_str(&attribute.name);
+        unquoted_end = false;
+        if options.collapse_boolean_attributes && is_boolean_attribute(tag, attribute) {
+            continue;
+        }
+        if let Some(value) = &attribute.value {
+            let minified;
+            let value = if options.minify_css && attribute.name.eq_ignore_ascii_case("style") {
+                minified = css::minify_inline(value);
+                &minified
+            } else {
+                value
+            };
+            let quote = if options.remove_attribute_quotes && is_unquotable(value) {
+                None
+            } else {
+                attribute.quote
+            };
+            out.push('=');
+            out.extend(quote);
+            out.push_str(value);
+            out.extend(quote);
+            unquoted_end = quote.is_none();
+        }
     }
-    if minifier.last_was_tag_end && item1.is_whitespace() {
-        match item2 {
-            None => return true,
-            Some(c) if c.is_whitespace() => return true,
-            _ => {}
+    if tag.self_closing {
+        if unquoted_end {
+            out.push(' ');
         }
+        out.push('/');
+    }
+    out.push('>');
+}
+
+/// Checks whether an attribute value can be written without quotes
+fn is_unquotable(value: &str) -> bool {
+    !value.is_empty()
+        && !value.chars().any(|item| {
+            item.is_ascii_whitespace() || matches!(item, '"' | '\'' | '=' | '<' | '>' | '`')
+        })
+}
+
+/// Checks whether an attribute is a boolean attribute of the tag whose value
+/// can be omitted: `disabled="disabled"` => `disabled`
+fn is_boolean_attribute(tag: &Tag, attribute: &Attribute) -> bool {
+    let is_collapsible = attribute
+        .value
+        .as_ref()
+        .is_none_or(|value| value.is_empty() || value.eq_ignore_ascii_case(&attribute.name));
+    is_collapsible
+        && BOOLEAN_ATTRIBUTES.iter().any(|(name, elements)| {
+            attribute.name.eq_ignore_ascii_case(name)
+                && (elements.is_empty() || elements.iter().any(|element| tag.is(element)))
+        })
+}
+
+/// Checks whether an attribute only repeats its default value:
+/// `<script type="text/javascript">` => `<script>`
+fn is_default_attribute(tag: &Tag, attribute: &Attribute) -> bool {
+    let Some(value) = &attribute.value else {
+        return false;
+    };
+    let is_default = DEFAULT_ATTRIBUTES.iter().any(|(element, name, default)| {
+        tag.is(element)
+            && attribute.name.eq_ignore_ascii_case(name)
+            && value.eq_ignore_ascii_case(default)
+    });
+    is_default && (!tag.is("link") || is_stylesheet(tag))
+}
+
+/// Checks whether minified script content contains an end tag or comment
+/// start, which the original content does not and which would end the script
+/// early
+fn ends_script_early(text: &str, minified: &str) -> bool {
+    let contains = |content: &str, pattern: &str| {
+        content
+            .as_bytes()
+            .windows(pattern.len())
+            .any(|window| window.eq_ignore_ascii_case(pattern.as_bytes()))
+    };
+    ["</script", "<!--"]
+        .iter()
+        .any(|pattern| contains(minified, pattern) && !contains(text, pattern))
+}
+
+/// Returns the language of the raw text started by the tag, based on its
+/// `type` attribute
+fn raw_text_of(tag: &Tag) -> RawText {
+    let kind = tag
+        .attributes
+        .iter()
+        .find(|attribute| attribute.name.eq_ignore_ascii_case("type"))
+        .and_then(|attribute| attribute.value.as_deref())
+        .map_or("", |value| {
+            value
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim_matches(|item: char| item.is_ascii_whitespace())
+        });
+    let is_kind = |kinds: &[&str]| kinds.iter().any(|other| kind.eq_ignore_ascii_case(other));
+    if tag.is("style") && (kind.is_empty() || kind.eq_ignore_ascii_case("text/css")) {
+        RawText::Css
+    } else if tag.is("script") && (kind.is_empty() || is_kind(&JS_TYPES)) {
+        RawText::Js
+    } else if tag.is("script") && is_kind(&JSON_TYPES) {
+        RawText::Json
+    } else {
+        RawText::Other
     }
-    minifier.last_was_tag_start = item1.eq(&'<');
-    minifier.last_was_tag_end = item1.eq(&'>');
-    false
+}
+
+fn is_stylesheet(tag: &Tag) -> bool {
+    tag.attributes.iter().any(|attribute| {
+        attribute.name.eq_ignore_ascii_case("rel")
+            && attribute.value.as_ref().is_some_and(|value| {
+                value
+                    .split_ascii_whitespace()
+                    .any(|rel| rel.eq_ignore_ascii_case("stylesheet"))
+            })
+    })
 }
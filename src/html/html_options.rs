@@ -0,0 +1,183 @@
+/// Options for HTML minification
+///
+/// The default options apply the same rules as [`minify`](super::minify).
+/// Every setter consumes and returns the options, so they can be chained:
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::html::HtmlOptions;
+///
+/// fn main() {
+///     let options = HtmlOptions::new()
+///         .keep_comments(true)
+///         .preserve_line_breaks(true);
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HtmlOptions {
+    pub(crate) keep_comments: bool,
+    pub(crate) keep_conditional_comments: bool,
+    pub(crate) collapse_whitespace: bool,
+    pub(crate) preserve_line_breaks: bool,
+    pub(crate) remove_control_characters: bool,
+    pub(crate) remove_attribute_quotes: bool,
+    pub(crate) collapse_boolean_attributes: bool,
+    pub(crate) remove_default_attributes: bool,
+    pub(crate) omit_optional_tags: bool,
+    pub(crate) minify_css: bool,
+    pub(crate) minify_js: bool,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HtmlOptions {
+    /// Creates the default options
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            keep_comments: false,
+            keep_conditional_comments: true,
+            collapse_whitespace: true,
+            preserve_line_breaks: false,
+            remove_control_characters: true,
+            remove_attribute_quotes: false,
+            collapse_boolean_attributes: false,
+            remove_default_attributes: false,
+            omit_optional_tags: false,
+            minify_css: true,
+            minify_js: true,
+        }
+    }
+
+    /// Keep all comments instead of removing them (default: `false`)
+    #[must_use]
+    pub const fn keep_comments(mut self, keep_comments: bool) -> Self {
+        self.keep_comments = keep_comments;
+        self
+    }
+
+    /// Keep conditional comments like `<!--[if IE]> ... <![endif]-->`
+    /// (default: `true`)
+    #[must_use]
+    pub const fn keep_conditional_comments(mut self, keep_conditional_comments: bool) -> Self {
+        self.keep_conditional_comments = keep_conditional_comments;
+        self
+    }
+
+    /// Remove multiple whitespaces and whitespaces around tags
+    /// (default: `true`)
+    ///
+    /// If disabled, whitespace characters are never removed, including
+    /// line breaks and tabs.
+    #[must_use]
+    pub const fn collapse_whitespace(mut self, collapse_whitespace: bool) -> Self {
+        self.collapse_whitespace = collapse_whitespace;
+        self
+    }
+
+    /// Keep a single line break wherever the input contained at least one
+    /// (default: `false`)
+    ///
+    /// Whitespaces directly surrounding a kept line break are removed.
+    #[must_use]
+    pub const fn preserve_line_breaks(mut self, preserve_line_breaks: bool) -> Self {
+        self.preserve_line_breaks = preserve_line_breaks;
+        self
+    }
+
+    /// Remove ascii control characters which are not kept as whitespace
+    /// (default: `true`)
+    #[must_use]
+    pub const fn remove_control_characters(mut self, remove_control_characters: bool) -> Self {
+        self.remove_control_characters = remove_control_characters;
+        self
+    }
+
+    /// Remove quotes around attribute values where html allows it
+    /// (default: `false`)
+    ///
+    /// Quotes are kept if the value is empty or contains whitespaces or one of
+    /// `"`, `'`, `=`, `<`, `>` and `` ` ``: `class="a"` => `class=a`
+    #[must_use]
+    pub const fn remove_attribute_quotes(mut self, remove_attribute_quotes: bool) -> Self {
+        self.remove_attribute_quotes = remove_attribute_quotes;
+        self
+    }
+
+    /// Remove the value of boolean attributes (default: `false`)
+    ///
+    /// `checked="checked"` and `selected=""` => `checked` and `selected`
+    #[must_use]
+    pub const fn collapse_boolean_attributes(mut self, collapse_boolean_attributes: bool) -> Self {
+        self.collapse_boolean_attributes = collapse_boolean_attributes;
+        self
+    }
+
+    /// Remove attributes which only repeat their default value
+    /// (default: `false`)
+    ///
+    /// Removes `type="text/javascript"` from `script`, `type="text/css"` from
+    /// `style` and stylesheet `link`s, `method="get"` from `form` and
+    /// `type="text"` from `input` elements.
+    #[must_use]
+    pub const fn remove_default_attributes(mut self, remove_default_attributes: bool) -> Self {
+        self.remove_default_attributes = remove_default_attributes;
+        self
+    }
+
+    /// Omit start and end tags which are optional according to the html
+    /// specification (default: `false`)
+    ///
+    /// End tags like `</li>`, `</p>`, `</td>` or `</option>` are only omitted
+    /// if the following sibling or the end of the parent element allows it.
+    /// Start tags of `html`, `head` and `body` are omitted if they have no
+    /// attributes.
+    #[must_use]
+    pub const fn omit_optional_tags(mut self, omit_optional_tags: bool) -> Self {
+        self.omit_optional_tags = omit_optional_tags;
+        self
+    }
+
+    /// Minify the content of `style` elements and attributes by css
+    /// minification rules (default: `true`)
+    ///
+    /// `style` elements with a `type` other than `text/css` are kept as is.
+    #[must_use]
+    pub const fn minify_css(mut self, minify_css: bool) -> Self {
+        self.minify_css = minify_css;
+        self
+    }
+
+    /// Minify the content of `script` elements by javascript or json
+    /// minification rules depending on their `type` (default: `true`)
+    ///
+    /// Scripts without a `type` or with a javascript type like `module` are
+    /// minified as javascript, `application/json`, `application/ld+json` and
+    /// `importmap` as json. Other types like `text/template` are kept as is.
+    #[must_use]
+    pub const fn minify_js(mut self, minify_js: bool) -> Self {
+        self.minify_js = minify_js;
+        self
+    }
+}
+
+impl HtmlOptions {
+    /// Checks whether a comment is kept
+    pub(crate) fn keeps_comment(self, comment: &str) -> bool {
+        let is_conditional = comment.starts_with('[') && comment.ends_with(']');
+        self.keep_comments || is_conditional && self.keep_conditional_comments
+    }
+
+    /// Checks whether text which only consists of whitespaces is written
+    pub(crate) fn keeps_whitespace(self, whitespace: &str) -> bool {
+        if self.collapse_whitespace {
+            whitespace.contains(' ') || self.preserve_line_breaks && whitespace.contains('\n')
+        } else {
+            !whitespace.is_empty()
+        }
+    }
+}
@@ -1,24 +1,45 @@
+// the tests keep the raw strings of their original form
+#![cfg_attr(test, allow(clippy::needless_raw_string_hashes))]
+
+pub use crate::html::html_options::HtmlOptions;
+#[cfg(any(feature = "tokio", feature = "futures"))]
+use crate::io::async_io::{InternalAsyncReader, InternalAsyncWriter};
 use crate::{
-    html::html_minifier::{keep_element, HtmlMinifier},
-    io::{multi_filter::MultiFilter, reader::InternalReader},
+    html::html_minifier::HtmlMinifier,
+    io::{
+        minifier::InternalMinifier,
+        reader::{Filter, InternalReader},
+        stream::StreamFilter,
+        writer::InternalWriter,
+    },
 };
-use std::{io::Read, str::Chars};
+use std::io::{Read, Write};
 
 mod html_minifier;
+mod html_options;
+mod optional_tags;
+mod tokenizer;
 
-type HtmlMethod = fn(
-    &mut HtmlMinifier,
-    char,
-    Option<char>,
-    Option<char>,
-    Option<char>,
-    Option<char>,
-    Option<char>,
-) -> bool;
-type HtmlFilter<'a> = MultiFilter<Chars<'a>, HtmlMethod, HtmlMinifier>;
+type HtmlFilter<I> = StreamFilter<I, HtmlMinifier>;
 
 /// Reader Implementation for HTML minification
-pub type Reader<R> = InternalReader<R, HtmlMethod, HtmlMinifier>;
+pub type Reader<R> = InternalReader<HtmlFilter<Filter<R>>>;
+
+/// Writer Implementation for HTML minification
+pub type Writer<W> = InternalWriter<W, HtmlMinifier>;
+
+/// Incremental Minifier Implementation for HTML minification
+pub type Minifier = InternalMinifier<HtmlMinifier>;
+
+/// Async Reader Implementation for HTML minification, implementing the
+/// `AsyncRead` trait of the enabled `tokio` or `futures` feature
+#[cfg(any(feature = "tokio", feature = "futures"))]
+pub type AsyncReader<R> = InternalAsyncReader<R, HtmlMinifier>;
+
+/// Async Writer Implementation for HTML minification, implementing the
+/// `AsyncWrite` trait of the enabled `tokio` or `futures` feature
+#[cfg(any(feature = "tokio", feature = "futures"))]
+pub type AsyncWriter<W> = InternalAsyncWriter<W, HtmlMinifier>;
 
 /// Minifies a given String by HTML minification rules
 ///
@@ -43,8 +64,33 @@ pub type Reader<R> = InternalReader<R, HtmlMethod, HtmlMinifier>;
 #[inline]
 #[must_use]
 pub fn minify(html: &str) -> String {
+    minify_with_options(html, HtmlOptions::default())
+}
+
+/// Minifies a given String by HTML minification rules, configured by the
+/// given options
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::html::{minify_with_options, HtmlOptions};
+///
+/// fn main() {
+///     let html = r#"
+///         <html>
+///             <!-- keep me -->
+///         <html>
+///     "#;
+///     let options = HtmlOptions::new().keep_comments(true);
+///     let html_minified = minify_with_options(html, options);
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn minify_with_options(html: &str, options: HtmlOptions) -> String {
     let filtered = html.chars();
-    HtmlFilter::new(filtered, keep_element).collect()
+    HtmlFilter::new(filtered, HtmlMinifier::with_options(options)).collect()
 }
 
 /// Minifies a given Read by HTML minification rules
@@ -65,7 +111,188 @@ pub fn minify(html: &str) -> String {
 /// ```
 #[inline]
 pub fn minify_from_read<R: Read>(html: R) -> Reader<R> {
-    Reader::new(html, keep_element)
+    minify_from_read_with_options(html, HtmlOptions::default())
+}
+
+/// Minifies a given Read by HTML minification rules, configured by the
+/// given options
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use std::fs::File;
+/// use std::io::Read;
+/// use minify::html::{minify_from_read_with_options, HtmlOptions};
+///
+/// fn main() {
+///     let mut html_minified = String::new();
+///     let mut file = File::open("tests/files/test.html").expect("file not found");
+///     let options = HtmlOptions::new().keep_conditional_comments(false);
+///     minify_from_read_with_options(file, options).read_to_string(&mut html_minified);
+/// }
+/// ```
+#[inline]
+pub fn minify_from_read_with_options<R: Read>(html: R, options: HtmlOptions) -> Reader<R> {
+    Reader::from_read(html, |chars| {
+        HtmlFilter::new(chars, HtmlMinifier::with_options(options))
+    })
+}
+
+/// Minifies everything written to the returned Writer by HTML
+/// minification rules and writes the result into the given Write
+///
+/// Output which depends on the following input is held back until more
+/// input is written or the Writer is finished by `Writer::finish` or
+/// dropped.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use std::io::Write;
+/// use minify::html::minify_to_write;
+///
+/// fn main() {
+///     let mut writer = minify_to_write(Vec::new());
+///     writer.write_all(b"<p>  test  </p>").expect("error at write");
+///     let html_minified = writer.finish().expect("error at write");
+/// }
+/// ```
+#[inline]
+pub fn minify_to_write<W: Write>(html: W) -> Writer<W> {
+    minify_to_write_with_options(html, HtmlOptions::default())
+}
+
+/// Minifies everything written to the returned Writer by HTML
+/// minification rules, configured by the given options, and writes the
+/// result into the given Write
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use std::io::Write;
+/// use minify::html::{minify_to_write_with_options, HtmlOptions};
+///
+/// fn main() {
+///     let options = HtmlOptions::new().keep_comments(true);
+///     let mut writer = minify_to_write_with_options(Vec::new(), options);
+///     writer.write_all(b"<!-- comment -->  <p>").expect("error at write");
+///     let html_minified = writer.finish().expect("error at write");
+/// }
+/// ```
+#[inline]
+pub const fn minify_to_write_with_options<W: Write>(html: W, options: HtmlOptions) -> Writer<W> {
+    Writer::new(html, HtmlMinifier::with_options(options))
+}
+
+/// Creates a Minifier, which minifies input fed in chunks by HTML
+/// minification rules
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::html::minifier;
+///
+/// fn main() {
+///     let mut minifier = minifier();
+///     let mut html_minified: String = minifier.feed("<p>  te").collect();
+///     html_minified.extend(minifier.feed("st  </p>"));
+///     html_minified.push_str(&minifier.finish().expect("incomplete input"));
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn minifier() -> Minifier {
+    minifier_with_options(HtmlOptions::default())
+}
+
+/// Creates a Minifier, which minifies input fed in chunks by HTML
+/// minification rules, configured by the given options
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::html::{minifier_with_options, HtmlOptions};
+///
+/// fn main() {
+///     let options = HtmlOptions::new().keep_comments(true);
+///     let mut minifier = minifier_with_options(options);
+///     let mut html_minified = Vec::new();
+///     minifier
+///         .feed_bytes(b"<!-- comment -->  <p>", &mut html_minified)
+///         .expect("invalid utf8");
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub const fn minifier_with_options(options: HtmlOptions) -> Minifier {
+    Minifier::new(HtmlMinifier::with_options(options))
+}
+
+/// Minifies a given async reader by HTML minification rules
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::html::minify_from_async_read;
+///
+/// fn main() {
+///     let html: &[u8] = b"<p>  test  </p>";
+///     // read the minified html like any other `AsyncRead`
+///     let reader = minify_from_async_read(html);
+/// }
+/// ```
+#[cfg(any(feature = "tokio", feature = "futures"))]
+#[inline]
+pub fn minify_from_async_read<R>(html: R) -> AsyncReader<R> {
+    minify_from_async_read_with_options(html, HtmlOptions::default())
+}
+
+/// Minifies a given async reader by HTML minification rules, configured by
+/// the given options
+#[cfg(any(feature = "tokio", feature = "futures"))]
+#[inline]
+pub fn minify_from_async_read_with_options<R>(html: R, options: HtmlOptions) -> AsyncReader<R> {
+    AsyncReader::new(html, HtmlMinifier::with_options(options))
+}
+
+/// Minifies everything written to the returned async writer by HTML
+/// minification rules and writes the result into the given async writer
+///
+/// The remaining output is written when the writer is shut down or closed.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::html::minify_to_async_write;
+///
+/// fn main() {
+///     // write the html to minify like to any other `AsyncWrite`
+///     let writer = minify_to_async_write(Vec::<u8>::new());
+/// }
+/// ```
+#[cfg(any(feature = "tokio", feature = "futures"))]
+#[inline]
+pub fn minify_to_async_write<W>(html: W) -> AsyncWriter<W> {
+    minify_to_async_write_with_options(html, HtmlOptions::default())
+}
+
+/// Minifies everything written to the returned async writer by HTML
+/// minification rules, configured by the given options, and writes the
+/// result into the given async writer
+#[cfg(any(feature = "tokio", feature = "futures"))]
+#[inline]
+pub const fn minify_to_async_write_with_options<W>(
+    html: W,
+    options: HtmlOptions,
+) -> AsyncWriter<W> {
+    AsyncWriter::new(html, HtmlMinifier::with_options(options))
 }
 
 #[test]
@@ -225,3 +452,472 @@ fn keep_pre() {
     let actual = minify(input);
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn removal_from_read_with_options() {
+    use std::fs::File;
+
+    let file = File::open("tests/files/test.html").expect("file not found");
+    let expected = "<html><head></head><body></body><html>";
+    let options = HtmlOptions::new().keep_conditional_comments(false);
+    let mut actual = String::new();
+    let _ = minify_from_read_with_options(file, options)
+        .read_to_string(&mut actual)
+        .expect("error at read");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_comments_with_option() {
+    let input = r"
+            <html>
+                <!-- comment data -->
+            <html>
+        ";
+    let expected = "<html> <!-- comment data --> <html>";
+    let options = HtmlOptions::new().keep_comments(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn removal_of_conditional_comments_with_option() {
+    let input = r"
+            <html>
+                <!--[if lte IE 8]>
+                Important comment test
+                <![endif]-->
+            <html>
+        ";
+    let expected = "<html> <html>";
+    let options = HtmlOptions::new().keep_conditional_comments(false);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_whitespace_without_collapse() {
+    let input = "<html>\n  <body>  test\ttest  </body>\n</html>";
+    let options = HtmlOptions::new().collapse_whitespace(false);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, input);
+}
+
+#[test]
+fn keep_line_breaks_with_option() {
+    let input = r"
+            <html>
+                <head>
+
+                </head>
+                <body>  test
+                  settings
+                </body>
+            <html>
+        ";
+    let expected = "<html>\n<head>\n</head>\n<body> test\nsettings\n</body>\n<html>\n";
+    let options = HtmlOptions::new().preserve_line_breaks(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_textarea() {
+    let input = "<textarea  name=\"a\">\n  first line\n\n  <b>second</b>  line\n</textarea>  <p>";
+    let expected = "<textarea name=\"a\">\n  first line\n\n  <b>second</b>  line\n</textarea> <p>";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_script() {
+    let input = r#"
+            <script>
+                var comment = "<!-- not a comment -->";
+                var a = 1
+                var b = 2
+            </script>
+            <p>  test  </p>
+        "#;
+    let expected = r#"<script>
+                var comment = "<!-- not a comment -->";
+                var a = 1
+                var b = 2
+            </script> <p> test </p>"#;
+    let options = HtmlOptions::new().minify_js(false);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_style() {
+    let input = "<style>\n  a  >  b { color: red; }\n</STYLE >  <p>";
+    let expected = "<style>\n  a  >  b { color: red; }\n</STYLE> <p>";
+    let options = HtmlOptions::new().minify_css(false);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_script_until_matching_end_tag() {
+    let input = "<script>\n  a = '</scripts>';\n</script>  <p>";
+    let expected = "<script>a='</scripts>';</script> <p>";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn minify_elements_starting_with_pre() {
+    let input = "<preview>  a  </preview>  <prefix-element>  b  </prefix-element>";
+    let expected = "<preview> a </preview> <prefix-element> b </prefix-element>";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_pre_case_insensitive_with_attributes() {
+    let input = "<PRE  class=\"code\">  a  </PRE>  b";
+    let expected = "<PRE class=\"code\">  a  </PRE> b";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_nested_pre_until_matching_end_tag() {
+    let input = "<pre>  a <pre>  b  </pre>  c  </prefix>  d  </pre>  e";
+    let expected = "<pre>  a <pre>  b  </pre>  c  </prefix>  d  </pre> e";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_greater_than_sign_in_quoted_attribute() {
+    let input = r#"<a  title="a > b"  href='/'  >  link  </a>"#;
+    let expected = r#"<a title="a > b" href='/'> link </a>"#;
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_doctype_cdata_and_declarations() {
+    let input = r#"
+            <?xml version="1.0"?>
+            <!DOCTYPE   html >
+            <svg><![CDATA[  a  <  b  ]]></svg>
+        "#;
+    let expected = r#"<?xml version="1.0"?> <!DOCTYPE html> <svg><![CDATA[  a  <  b  ]]></svg>"#;
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_space_before_self_closing_unquoted_attribute() {
+    let input = "<img src=a.png />  <br />";
+    let expected = "<img src=a.png /> <br/>";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_text_with_less_than_sign() {
+    let input = "<p>1 <= 2</p>";
+    let expected = "<p>1 <= 2</p>";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_whitespace_inside_of_attribute_values() {
+    let input = "<input  title=\"a   b\"\n    value='  c\n d  '   alt = \"e  f\"  >";
+    let expected = "<input title=\"a   b\" value='  c\n d  ' alt=\"e  f\">";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_whitespace_inside_of_attribute_values_from_read() {
+    let input = "<meta   name=\"description\"   content=\"a  long\ttext\">";
+    let expected = "<meta name=\"description\" content=\"a  long\ttext\">";
+    let mut actual = String::new();
+    let _ = minify_from_read(input.as_bytes())
+        .read_to_string(&mut actual)
+        .expect("error at read");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn removal_of_attribute_quotes_with_option() {
+    let input =
+        r#"<div class="a" id='b' title="c d" data-e="" data-f="g=h"><img src="i.png"/></div>"#;
+    let expected =
+        r#"<div class=a id=b title="c d" data-e="" data-f="g=h"><img src=i.png /></div>"#;
+    let options = HtmlOptions::new().remove_attribute_quotes(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_attribute_quotes() {
+    let input = r#"<div class="a" id='b'></div>"#;
+    let actual = minify(input);
+    assert_eq!(actual, input);
+}
+
+#[test]
+fn collapse_boolean_attributes_with_option() {
+    let input = r#"<select multiple="multiple"><option selected="" value="a">a</option></select>
+        <input type="checkbox" checked="Checked" disabled="false"><div hidden="hidden" checked="checked"></div>"#;
+    let expected = r#"<select multiple><option selected value="a">a</option></select> <input type="checkbox" checked disabled="false"><div hidden checked="checked"></div>"#;
+    let options = HtmlOptions::new().collapse_boolean_attributes(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn removal_of_default_attributes_with_option() {
+    let input = r#"<script type="text/javascript" src="a.js"></script><style type="TEXT/CSS"></style>
+        <link rel="stylesheet" type="text/css" href="a.css"><link rel="icon" type="text/css">
+        <form method="get"><input type="text" name="q"><input type="submit"></form>"#;
+    let expected = r#"<script src="a.js"></script><style></style> <link rel="stylesheet" href="a.css"><link rel="icon" type="text/css"> <form><input name="q"><input type="submit"></form>"#;
+    let options = HtmlOptions::new().remove_default_attributes(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn omit_optional_tags_with_option() {
+    let input = r#"
+            <html>
+                <head>
+                    <title>Test</title>
+                </head>
+                <body>
+                    <ul>
+                        <li>a</li>
+                        <li>b</li>
+                    </ul>
+                    <p>c</p>
+                    <div><p>d</p></div>
+                    <a href="/"><p>e</p></a>
+                    <table>
+                        <tr><td>f</td><td>g</td></tr>
+                        <tr><th>h</th></tr>
+                    </table>
+                    <select><option>i</option><!-- comment --><option>j</option></select>
+                </body>
+            </html>
+        "#;
+    let expected = "<title>Test</title> </head> <body> <ul> <li>a <li>b </ul> <p>c \
+         <div><p>d</div> <a href=\"/\"><p>e</p></a> <table> <tr><td>f<td>g \
+         <tr><th>h </table> <select><option>i<option>j</select>";
+    let options = HtmlOptions::new().omit_optional_tags(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn omit_optional_tags_without_whitespace() {
+    let input = "<html><head><title>Test</title></head><body><p>a</p><!-- b --></body></html>";
+    let expected = "<title>Test</title><p>a";
+    let options = HtmlOptions::new().omit_optional_tags(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn omit_p_end_tag_before_search() {
+    let input = "<p>a</p><search><p>b</p></search>";
+    let expected = "<p>a<search><p>b</search>";
+    let options = HtmlOptions::new().omit_optional_tags(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_optional_tags_with_attributes_or_comments() {
+    let input = "<html lang=\"en\"><body class=\"a\"><dl><dt>a</dt><dd>b</dd></dl><p>c</p><!--[if IE]>d<![endif]--></body></html>";
+    let expected =
+        "<html lang=\"en\"><body class=\"a\"><dl><dt>a<dd>b</dl><p>c</p><!--[if IE]>d<![endif]-->";
+    let options = HtmlOptions::new().omit_optional_tags(true);
+    let actual = minify_with_options(input, options);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn minify_style() {
+    let input = "<style>\n  a  >  b { color : red ; }\n  /* comment */\n  @media screen and (max-width : 10px) {\n    a:hover , .b  :first-child { margin : 0  auto ; }\n  }\n</style>  <p>";
+    let expected = "<style>a>b{color:red}@media screen and (max-width:10px){a:hover,.b :first-child{margin:0 auto}}</style> <p>";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn minify_style_attribute() {
+    let input = r#"<p style=" color : red ; background : url( 'a b.png' ) ; "></p><p style='font-family: "a  b" , serif;'></p>"#;
+    let expected = r#"<p style="color:red;background:url('a b.png')"></p><p style='font-family:"a  b",serif'></p>"#;
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_style_with_other_type() {
+    let input = "<style type=\"text/less\">\n  a { b: c; }\n</style>";
+    let actual = minify(input);
+    assert_eq!(actual, input);
+}
+
+#[test]
+fn minify_script() {
+    let input = "<script>\n  // comment\n  var a = 1\n  var b = '</p>  <p>'\n</script>  <script type=\"module\">  import a  from 'b' ;  </script>";
+    let expected =
+        "<script>var a=1\nvar b='</p>  <p>'</script> <script type=\"module\">import a from'b';</script>";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn minify_script_without_end_tag() {
+    let input = "<script>if (a < /script>/.test(b)) { c() }</script><script>x = a < /script/i.test(s)</script>";
+    let expected =
+        "<script>if(a< /script>/.test(b)){c()}</script><script>x=a< /script/i.test(s)</script>";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_script_if_minified_script_starts_comment() {
+    let input = "<script>x = a <!/**/--b</script>";
+    let actual = minify(input);
+    assert_eq!(actual, input);
+}
+
+#[test]
+fn minify_json_script() {
+    let input = r#"<script type="application/ld+json"> { "a" : [ 1 , "b  c" ] } </script><script type="importmap">{ "imports" : { } }</script>"#;
+    let expected = r#"<script type="application/ld+json">{"a":[1,"b  c"]}</script><script type="importmap">{"imports":{}}</script>"#;
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_script_with_other_type() {
+    let input = "<script type=\"text/template\">\n  <p>  {{ a }}  </p>\n</script>";
+    let actual = minify(input);
+    assert_eq!(actual, input);
+}
+
+#[test]
+fn return_error_for_invalid_utf8_from_read() {
+    use crate::CharsError;
+    use std::io::ErrorKind;
+
+    let input: &[u8] = b"<p>  a  \xff</p>";
+    let mut actual = String::new();
+    let error = minify_from_read(input)
+        .read_to_string(&mut actual)
+        .expect_err("invalid utf8 is read");
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    let inner = error
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<CharsError>())
+        .expect("error is no CharsError");
+    assert_eq!(inner.offset(), 8);
+}
+
+#[test]
+fn return_no_output_after_invalid_utf8_from_read() {
+    let input: &[u8] = b"<p>  a  \xff <b>c</b></p>";
+    let mut actual = Vec::new();
+    let _ = minify_from_read(input)
+        .read_to_end(&mut actual)
+        .expect_err("invalid utf8 is read");
+    assert_eq!(actual, b"<p> a");
+}
+
+#[test]
+fn replace_invalid_utf8_from_read_with_lossy() {
+    let input: &[u8] = b"<p>  a  \xff</p>";
+    let expected = "<p> a \u{fffd}</p>";
+    let mut actual = String::new();
+    let _ = minify_from_read(input)
+        .lossy(true)
+        .read_to_string(&mut actual)
+        .expect("error at read");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn removal_to_write() {
+    let input = "<html>\n  <p>  a  €  </p>\n  <!-- comment -->  <pre>  b  </pre>\n</html>";
+    let mut writer = minify_to_write(Vec::new());
+    // multibyte characters are split between writes
+    for chunk in input.as_bytes().chunks(3) {
+        writer.write_all(chunk).expect("error at write");
+    }
+    let actual = writer.finish().expect("error at write");
+    assert_eq!(
+        String::from_utf8(actual).expect("invalid utf8"),
+        minify(input)
+    );
+}
+
+#[test]
+fn removal_with_minifier() {
+    let input = "<html>\n  <p>  a  €  </p>\n  <!-- comment -->  <pre>  b  </pre>\n</html>";
+    let mut minifier = minifier();
+    let mut actual = Vec::new();
+    for chunk in input.as_bytes().chunks(3) {
+        minifier
+            .feed_bytes(chunk, &mut actual)
+            .expect("error at feed");
+    }
+    actual.extend(minifier.finish().expect("error at finish").bytes());
+    assert_eq!(
+        String::from_utf8(actual).expect("invalid utf8"),
+        minify(input)
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn removal_from_tokio_read_and_to_tokio_write() {
+    use std::{
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    let input = "<html>\n  <p>  a  €  </p>\n  <!-- comment -->  <pre>  b  </pre>\n</html>";
+    let mut cx = Context::from_waker(Waker::noop());
+
+    let mut reader = minify_from_async_read(input.as_bytes());
+    let mut actual = Vec::new();
+    let mut buf = [0; 5];
+    loop {
+        let mut read_buf = ReadBuf::new(&mut buf);
+        let poll = Pin::new(&mut reader).poll_read(&mut cx, &mut read_buf);
+        assert!(matches!(poll, Poll::Ready(Ok(()))));
+        if read_buf.filled().is_empty() {
+            break;
+        }
+        actual.extend_from_slice(read_buf.filled());
+    }
+    assert_eq!(
+        String::from_utf8(actual).expect("invalid utf8"),
+        minify(input)
+    );
+
+    let mut writer = minify_to_async_write(Vec::new());
+    for chunk in input.as_bytes().chunks(3) {
+        let poll = Pin::new(&mut writer).poll_write(&mut cx, chunk);
+        assert!(matches!(poll, Poll::Ready(Ok(len)) if len == chunk.len()));
+    }
+    let poll = Pin::new(&mut writer).poll_shutdown(&mut cx);
+    assert!(matches!(poll, Poll::Ready(Ok(()))));
+    let actual = writer.into_inner();
+    assert_eq!(
+        String::from_utf8(actual).expect("invalid utf8"),
+        minify(input)
+    );
+}
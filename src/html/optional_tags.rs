@@ -0,0 +1,289 @@
+use crate::html::{
+    html_options::HtmlOptions,
+    tokenizer::{Tag, Token},
+};
+use std::mem;
+
+/// Elements without content and therefore without an end tag
+const VOID_ELEMENTS: [&str; 15] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "keygen", "link", "meta", "param",
+    "source", "track", "wbr",
+];
+
+/// Elements whose start tag closes an open `p` element
+const P_CLOSING_ELEMENTS: [&str; 32] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hgroup",
+    "hr",
+    "main",
+    "menu",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "search",
+    "section",
+    "table",
+    "ul",
+];
+
+/// Parents in which the end tag of a `p` element has to be kept
+const P_KEEPING_PARENTS: [&str; 7] = ["a", "audio", "del", "ins", "map", "noscript", "video"];
+
+/// Elements which may not directly follow an omitted `body` start tag
+const BODY_KEEPING_ELEMENTS: [&str; 6] =
+    ["link", "meta", "noscript", "script", "style", "template"];
+
+/// A tag which is held back until the following token is known
+#[derive(Debug, Clone)]
+pub enum Pending {
+    StartTag(Tag),
+    EndTag(String, Option<String>),
+}
+
+/// Omission of optional tags as allowed by the html specification
+///
+/// Tokens are passed through unchanged, except that tags which may be
+/// optional are held back until the next token (and therefore the next
+/// sibling or the end of the parent) is known.
+#[derive(Debug, Clone, Default)]
+pub struct OptionalTags {
+    stack: Vec<String>,
+    pending: Option<Pending>,
+    skipped: Vec<Token>,
+}
+
+impl OptionalTags {
+    pub const fn new() -> Self {
+        Self {
+            stack: Vec::new(),
+            pending: None,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Returns the tokens which are ready to be written
+    pub fn filter(&mut self, options: HtmlOptions, tokens: Vec<Token>) -> Vec<Token> {
+        let mut ready = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            if let Some(pending) = self.pending.take() {
+                if is_skippable(options, &pending, &token) {
+                    self.pending = Some(pending);
+                    self.skipped.push(token);
+                    continue;
+                }
+                self.resolve(pending, Some(&token), &mut ready);
+            }
+            self.handle_token(token, &mut ready);
+        }
+        ready
+    }
+
+    /// Returns all tokens which are still held back
+    pub fn finish(&mut self) -> Vec<Token> {
+        let mut ready = Vec::new();
+        if let Some(pending) = self.pending.take() {
+            self.resolve(pending, None, &mut ready);
+        }
+        ready
+    }
+
+    fn handle_token(&mut self, token: Token, ready: &mut Vec<Token>) {
+        match token {
+            Token::StartTag(tag) => {
+                self.open_element(&tag);
+                let is_optional = tag.attributes.is_empty()
+                    && (tag.is("html") || tag.is("head") || tag.is("body"));
+                if is_optional {
+                    self.pending = Some(Pending::StartTag(tag));
+                } else {
+                    ready.push(Token::StartTag(tag));
+                }
+            }
+            Token::EndTag(name) => {
+                let name_lowercase = name.to_ascii_lowercase();
+                if let Some(pos) = self.stack.iter().rposition(|open| open == &name_lowercase) {
+                    self.stack.truncate(pos);
+                }
+                if is_optional_end_tag(&name_lowercase) {
+                    let parent = self.stack.last().cloned();
+                    self.pending = Some(Pending::EndTag(name, parent));
+                } else {
+                    ready.push(Token::EndTag(name));
+                }
+            }
+            token => ready.push(token),
+        }
+    }
+
+    fn open_element(&mut self, tag: &Tag) {
+        while let Some(open) = self.stack.last() {
+            if is_closed_by(open, tag) {
+                let _ = self.stack.pop();
+            } else {
+                break;
+            }
+        }
+        let is_void = VOID_ELEMENTS.iter().any(|element| tag.is(element));
+        if !is_void && !tag.self_closing {
+            self.stack.push(tag.name.to_ascii_lowercase());
+        }
+    }
+
+    fn resolve(&mut self, pending: Pending, next: Option<&Token>, ready: &mut Vec<Token>) {
+        if !is_omittable(&pending, next) {
+            ready.push(match pending {
+                Pending::StartTag(tag) => Token::StartTag(tag),
+                Pending::EndTag(name, _) => Token::EndTag(name),
+            });
+        }
+        ready.append(&mut mem::take(&mut self.skipped));
+    }
+}
+
+fn is_optional_end_tag(name: &str) -> bool {
+    matches!(
+        name,
+        "body"
+            | "dd"
+            | "dt"
+            | "head"
+            | "html"
+            | "li"
+            | "optgroup"
+            | "option"
+            | "p"
+            | "rp"
+            | "rt"
+            | "tbody"
+            | "td"
+            | "tfoot"
+            | "th"
+            | "thead"
+            | "tr"
+    )
+}
+
+/// Checks whether the start tag implicitly closes the open element
+fn is_closed_by(open: &str, tag: &Tag) -> bool {
+    match open {
+        "li" => tag.is("li"),
+        "dd" | "dt" => tag.is("dd") || tag.is("dt"),
+        "p" => P_CLOSING_ELEMENTS.iter().any(|element| tag.is(element)),
+        "rp" | "rt" => tag.is("rp") || tag.is("rt"),
+        "option" => tag.is("option") || tag.is("optgroup"),
+        "optgroup" => tag.is("optgroup"),
+        "td" | "th" => tag.is("td") || tag.is("th") || tag.is("tr"),
+        "tr" => tag.is("tr"),
+        "thead" | "tbody" => tag.is("tbody") || tag.is("tfoot"),
+        _ => false,
+    }
+}
+
+/// Checks whether a token can be skipped to find the token which decides
+/// about the pending tag. Removed comments and whitespaces are always
+/// skipped, kept whitespaces only where they are not rendered anyway.
+fn is_skippable(options: HtmlOptions, pending: &Pending, token: &Token) -> bool {
+    match token {
+        Token::Comment(comment) => !options.keeps_comment(comment),
+        Token::Text(text) if text.chars().all(|item| item.is_ascii_whitespace()) => {
+            !options.keeps_whitespace(text)
+                || match pending {
+                    Pending::StartTag(tag) => tag.is("head"),
+                    Pending::EndTag(name, _) => !["body", "head", "html", "rp", "rt"]
+                        .iter()
+                        .any(|element| name.eq_ignore_ascii_case(element)),
+                }
+        }
+        _ => false,
+    }
+}
+
+fn is_omittable(pending: &Pending, next: Option<&Token>) -> bool {
+    match pending {
+        Pending::StartTag(tag) => is_start_tag_omittable(tag, next),
+        Pending::EndTag(name, parent) => {
+            is_end_tag_omittable(&name.to_ascii_lowercase(), parent.as_deref(), next)
+        }
+    }
+}
+
+fn is_start_tag_omittable(tag: &Tag, next: Option<&Token>) -> bool {
+    if tag.is("html") {
+        !matches!(next, Some(Token::Comment(_)))
+    } else if tag.is("head") {
+        match next {
+            None | Some(Token::StartTag(_)) => true,
+            Some(Token::EndTag(name)) => name.eq_ignore_ascii_case("head"),
+            _ => false,
+        }
+    } else {
+        match next {
+            Some(Token::StartTag(next)) => {
+                !BODY_KEEPING_ELEMENTS.iter().any(|element| next.is(element))
+            }
+            Some(Token::Text(text)) => !text.starts_with(|item: char| item.is_ascii_whitespace()),
+            Some(Token::Comment(_)) => false,
+            _ => true,
+        }
+    }
+}
+
+fn is_end_tag_omittable(name: &str, parent: Option<&str>, next: Option<&Token>) -> bool {
+    let next_tag = match next {
+        Some(Token::StartTag(tag)) => Some(tag),
+        _ => None,
+    };
+    let is_followed_by = |elements: &[&str]| {
+        next_tag.is_some_and(|tag| elements.iter().any(|element| tag.is(element)))
+    };
+    let is_parent_end = match next {
+        None => true,
+        Some(Token::EndTag(end)) => parent.is_some_and(|parent| end.eq_ignore_ascii_case(parent)),
+        _ => false,
+    };
+    match name {
+        "html" | "body" => !matches!(next, Some(Token::Comment(_))),
+        "head" => match next {
+            Some(Token::Comment(_)) => false,
+            Some(Token::Text(text)) => !text.starts_with(|item: char| item.is_ascii_whitespace()),
+            _ => true,
+        },
+        "li" => is_followed_by(&["li"]) || is_parent_end,
+        "dt" => is_followed_by(&["dt", "dd"]),
+        "dd" => is_followed_by(&["dt", "dd"]) || is_parent_end,
+        "p" => {
+            is_followed_by(&P_CLOSING_ELEMENTS)
+                || is_parent_end
+                    && !parent.is_some_and(|parent| P_KEEPING_PARENTS.contains(&parent))
+        }
+        "rt" | "rp" => is_followed_by(&["rt", "rp"]) || is_parent_end,
+        "optgroup" => is_followed_by(&["optgroup"]) || is_parent_end,
+        "option" => is_followed_by(&["option", "optgroup"]) || is_parent_end,
+        "thead" => is_followed_by(&["tbody", "tfoot"]),
+        "tbody" => is_followed_by(&["tbody", "tfoot"]) || is_parent_end,
+        "tfoot" => is_parent_end,
+        "tr" => is_followed_by(&["tr"]) || is_parent_end,
+        "td" | "th" => is_followed_by(&["td", "th"]) || is_parent_end,
+        _ => false,
+    }
+}
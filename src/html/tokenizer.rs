@@ -0,0 +1,410 @@
+use std::mem;
+
+/// Elements whose content is not parsed as markup
+pub const RAW_TEXT_ELEMENTS: [&str; 3] = ["script", "style", "textarea"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    StartTag(Tag),
+    EndTag(String),
+    Text(String),
+    RawText(String),
+    Comment(String),
+    Doctype(String),
+    CData(String),
+    Declaration(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub attributes: Vec<Attribute>,
+    pub self_closing: bool,
+}
+
+impl Tag {
+    pub fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attribute {
+    pub name: String,
+    pub value: Option<String>,
+    pub quote: Option<char>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum State {
+    Data,
+    TagOpen,
+    EndTagOpen,
+    TagName,
+    BeforeAttributeName,
+    AttributeName,
+    AfterAttributeName,
+    BeforeAttributeValue,
+    AttributeValue(Option<char>),
+    AfterAttributeValueQuoted,
+    SelfClosingStartTag,
+    CommentInTag,
+    MarkupDeclarationOpen,
+    Comment,
+    Doctype,
+    CData,
+    Declaration,
+    RawText,
+}
+
+const COMMENT_START: &str = "--";
+const DOCTYPE_START: &str = "DOCTYPE";
+const CDATA_START: &str = "[CDATA[";
+
+/// Streaming html tokenizer
+///
+/// The tokenizer follows the html5 tokenization rules loosely. Unlike html5 it
+/// skips whitespaces between `<` and the tag name and comments inside of tags.
+#[derive(Debug, Clone)]
+pub struct Tokenizer {
+    state: State,
+    buffer: String,
+    tag: Tag,
+    is_end_tag: bool,
+    raw_element: &'static str,
+    matched: usize,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tokenizer {
+    pub const fn new() -> Self {
+        Self {
+            state: State::Data,
+            buffer: String::new(),
+            tag: Tag {
+                name: String::new(),
+                attributes: Vec::new(),
+                self_closing: false,
+            },
+            is_end_tag: false,
+            raw_element: "",
+            matched: 0,
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub fn push(&mut self, item: char, tokens: &mut Vec<Token>) {
+        match self.state {
+            State::Data => {
+                if item.eq(&'<') {
+                    self.emit_text(Token::Text, tokens);
+                    self.state = State::TagOpen;
+                } else {
+                    self.buffer.push(item);
+                }
+            }
+            State::TagOpen => match item {
+                '!' if self.buffer.is_empty() => self.state = State::MarkupDeclarationOpen,
+                '/' if self.buffer.is_empty() => self.state = State::EndTagOpen,
+                '?' if self.buffer.is_empty() => {
+                    self.buffer.push(item);
+                    self.state = State::Declaration;
+                }
+                item if item.is_ascii_alphabetic() => {
+                    self.buffer.clear();
+                    self.start_tag(item, false);
+                }
+                item if item.is_ascii_whitespace() => self.buffer.push(item),
+                '<' => {
+                    self.buffer.insert(0, '<');
+                    self.emit_text(Token::Text, tokens);
+                }
+                item => {
+                    self.buffer.insert(0, '<');
+                    self.buffer.push(item);
+                    self.state = State::Data;
+                }
+            },
+            State::EndTagOpen => match item {
+                item if item.is_ascii_alphabetic() => self.start_tag(item, true),
+                '>' => self.state = State::Data,
+                item if item.is_ascii_whitespace() => {}
+                item => {
+                    self.buffer.push('/');
+                    self.buffer.push(item);
+                    self.state = State::Declaration;
+                }
+            },
+            State::TagName => match item {
+                item if item.is_ascii_whitespace() => self.state = State::BeforeAttributeName,
+                '/' => self.state = State::SelfClosingStartTag,
+                '>' => self.emit_tag(tokens),
+                item => self.tag.name.push(item),
+            },
+            State::BeforeAttributeName => match item {
+                item if item.is_ascii_whitespace() => {}
+                '/' => self.state = State::SelfClosingStartTag,
+                '>' => self.emit_tag(tokens),
+                item => self.start_attribute(item),
+            },
+            State::AttributeName => match item {
+                item if item.is_ascii_whitespace() => self.state = State::AfterAttributeName,
+                '/' => self.state = State::SelfClosingStartTag,
+                '=' => self.state = State::BeforeAttributeValue,
+                '>' => self.emit_tag(tokens),
+                item => {
+                    if let Some(attribute) = self.tag.attributes.last_mut() {
+                        attribute.name.push(item);
+                        if attribute.name == "<!--" {
+                            let _ = self.tag.attributes.pop();
+                            self.matched = 0;
+                            self.state = State::CommentInTag;
+                        }
+                    }
+                }
+            },
+            State::AfterAttributeName => match item {
+                item if item.is_ascii_whitespace() => {}
+                '/' => self.state = State::SelfClosingStartTag,
+                '=' => self.state = State::BeforeAttributeValue,
+                '>' => self.emit_tag(tokens),
+                item => self.start_attribute(item),
+            },
+            State::BeforeAttributeValue => match item {
+                item if item.is_ascii_whitespace() => {}
+                '"' | '\'' => self.start_attribute_value(None, Some(item)),
+                '>' => {
+                    self.start_attribute_value(None, Some('"'));
+                    self.emit_tag(tokens);
+                }
+                item => self.start_attribute_value(Some(item), None),
+            },
+            State::AttributeValue(Some(quote)) => {
+                if item.eq(&quote) {
+                    self.state = State::AfterAttributeValueQuoted;
+                } else {
+                    self.push_attribute_value(item);
+                }
+            }
+            State::AttributeValue(None) => match item {
+                item if item.is_ascii_whitespace() => self.state = State::BeforeAttributeName,
+                '>' => self.emit_tag(tokens),
+                item => self.push_attribute_value(item),
+            },
+            State::AfterAttributeValueQuoted => match item {
+                item if item.is_ascii_whitespace() => self.state = State::BeforeAttributeName,
+                '/' => self.state = State::SelfClosingStartTag,
+                '>' => self.emit_tag(tokens),
+                item => self.start_attribute(item),
+            },
+            State::SelfClosingStartTag => match item {
+                '>' => {
+                    self.tag.self_closing = true;
+                    self.emit_tag(tokens);
+                }
+                item => {
+                    self.state = State::BeforeAttributeName;
+                    self.push(item, tokens);
+                }
+            },
+            State::CommentInTag => match item {
+                '-' => self.matched += 1,
+                '>' if self.matched >= 2 => self.state = State::BeforeAttributeName,
+                _ => self.matched = 0,
+            },
+            State::MarkupDeclarationOpen => {
+                self.buffer.push(item);
+                if self.buffer == COMMENT_START {
+                    self.buffer.clear();
+                    self.state = State::Comment;
+                } else if self.buffer.eq_ignore_ascii_case(DOCTYPE_START) {
+                    self.state = State::Doctype;
+                } else if self.buffer == CDATA_START {
+                    self.buffer.clear();
+                    self.state = State::CData;
+                } else if !is_prefix(&self.buffer, COMMENT_START, false)
+                    && !is_prefix(&self.buffer, DOCTYPE_START, true)
+                    && !is_prefix(&self.buffer, CDATA_START, false)
+                {
+                    let _ = self.buffer.pop();
+                    self.buffer.insert(0, '!');
+                    self.state = State::Declaration;
+                    self.push(item, tokens);
+                }
+            }
+            State::Comment => {
+                self.buffer.push(item);
+                if self.buffer.ends_with("-->") {
+                    self.buffer.truncate(self.buffer.len() - 3);
+                    self.emit_buffer(Token::Comment, tokens);
+                    self.state = State::Data;
+                }
+            }
+            State::Doctype => {
+                if item.eq(&'>') {
+                    self.emit_buffer(Token::Doctype, tokens);
+                    self.state = State::Data;
+                } else {
+                    self.buffer.push(item);
+                }
+            }
+            State::CData => {
+                self.buffer.push(item);
+                if self.buffer.ends_with("]]>") {
+                    self.buffer.truncate(self.buffer.len() - 3);
+                    self.emit_buffer(Token::CData, tokens);
+                    self.state = State::Data;
+                }
+            }
+            State::Declaration => {
+                if item.eq(&'>') {
+                    self.emit_buffer(Token::Declaration, tokens);
+                    self.state = State::Data;
+                } else {
+                    self.buffer.push(item);
+                }
+            }
+            State::RawText => self.push_raw_text(item, tokens),
+        }
+    }
+
+    pub fn finish(&mut self, tokens: &mut Vec<Token>) {
+        match self.state {
+            State::Data => self.emit_text(Token::Text, tokens),
+            State::TagOpen => {
+                self.buffer.insert(0, '<');
+                self.emit_text(Token::Text, tokens);
+            }
+            State::EndTagOpen => tokens.push(Token::Text(String::from("</"))),
+            State::TagName
+            | State::BeforeAttributeName
+            | State::AttributeName
+            | State::AfterAttributeName
+            | State::BeforeAttributeValue
+            | State::AttributeValue(_)
+            | State::AfterAttributeValueQuoted
+            | State::SelfClosingStartTag
+            | State::CommentInTag => self.emit_tag(tokens),
+            State::MarkupDeclarationOpen => {
+                self.buffer.insert(0, '!');
+                self.emit_buffer(Token::Declaration, tokens);
+            }
+            State::Comment => self.emit_buffer(Token::Comment, tokens),
+            State::Doctype => self.emit_buffer(Token::Doctype, tokens),
+            State::CData => self.emit_buffer(Token::CData, tokens),
+            State::Declaration => self.emit_buffer(Token::Declaration, tokens),
+            State::RawText => self.emit_text(Token::RawText, tokens),
+        }
+        self.state = State::Data;
+    }
+
+    fn push_raw_text(&mut self, item: char, tokens: &mut Vec<Token>) {
+        let end_len = self.raw_element.len() + 2;
+        if self.matched == end_len {
+            self.matched = 0;
+            if item.is_ascii_whitespace() || item.eq(&'>') || item.eq(&'/') {
+                let name = self
+                    .buffer
+                    .split_off(self.buffer.len() - self.raw_element.len());
+                self.buffer.truncate(self.buffer.len() - 2);
+                self.emit_text(Token::RawText, tokens);
+                self.tag = Tag {
+                    name,
+                    ..Tag::default()
+                };
+                self.is_end_tag = true;
+                self.state = State::TagName;
+                self.push(item, tokens);
+                return;
+            }
+        }
+        let expected = match self.matched {
+            0 => Some('<'),
+            1 => Some('/'),
+            pos => self.raw_element.chars().nth(pos - 2),
+        };
+        if expected.is_some_and(|c| c.eq_ignore_ascii_case(&item)) {
+            self.matched += 1;
+        } else {
+            self.matched = usize::from(item.eq(&'<'));
+        }
+        self.buffer.push(item);
+    }
+
+    fn start_tag(&mut self, item: char, is_end_tag: bool) {
+        self.tag = Tag::default();
+        self.tag.name.push(item);
+        self.is_end_tag = is_end_tag;
+        self.state = State::TagName;
+    }
+
+    fn start_attribute(&mut self, item: char) {
+        let mut attribute = Attribute::default();
+        attribute.name.push(item);
+        self.tag.attributes.push(attribute);
+        self.state = State::AttributeName;
+    }
+
+    fn start_attribute_value(&mut self, item: Option<char>, quote: Option<char>) {
+        if let Some(attribute) = self.tag.attributes.last_mut() {
+            let mut value = String::new();
+            value.extend(item);
+            attribute.value = Some(value);
+            attribute.quote = quote;
+        }
+        self.state = State::AttributeValue(quote);
+    }
+
+    fn push_attribute_value(&mut self, item: char) {
+        if let Some(value) = self
+            .tag
+            .attributes
+            .last_mut()
+            .and_then(|attribute| attribute.value.as_mut())
+        {
+            value.push(item);
+        }
+    }
+
+    fn emit_tag(&mut self, tokens: &mut Vec<Token>) {
+        let tag = mem::take(&mut self.tag);
+        self.state = State::Data;
+        if self.is_end_tag {
+            tokens.push(Token::EndTag(tag.name));
+            return;
+        }
+        if !tag.self_closing {
+            if let Some(element) = RAW_TEXT_ELEMENTS.iter().find(|element| tag.is(element)) {
+                self.raw_element = element;
+                self.matched = 0;
+                self.state = State::RawText;
+            }
+        }
+        tokens.push(Token::StartTag(tag));
+    }
+
+    fn emit_text(&mut self, token: fn(String) -> Token, tokens: &mut Vec<Token>) {
+        if !self.buffer.is_empty() {
+            self.emit_buffer(token, tokens);
+        }
+    }
+
+    fn emit_buffer(&mut self, token: fn(String) -> Token, tokens: &mut Vec<Token>) {
+        tokens.push(token(mem::take(&mut self.buffer)));
+    }
+}
+
+fn is_prefix(prefix: &str, value: &str, ignore_case: bool) -> bool {
+    value.get(..prefix.len()).is_some_and(|start| {
+        if ignore_case {
+            start.eq_ignore_ascii_case(prefix)
+        } else {
+            start == prefix
+        }
+    })
+}
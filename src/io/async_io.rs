@@ -0,0 +1,289 @@
+use crate::io::{minifier::InternalMinifier, stream::Stream};
+use std::{
+    fmt::{self, Formatter},
+    io::{Error, ErrorKind, Result},
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+/// Number of bytes read from the underlying reader at once
+const BUFFER_SIZE: usize = 8 * 1024;
+
+pub struct InternalAsyncReader<R, S> {
+    inner: R,
+    minifier: InternalMinifier<S>,
+    input: Box<[u8]>,
+    /// Number of bytes of the minified output which are already returned
+    pos_output: usize,
+    finished: bool,
+    /// Error which ended the input, returned after the remaining output
+    error: Option<Error>,
+}
+
+impl<R, S: Stream> InternalAsyncReader<R, S> {
+    pub fn new(inner: R, stream: S) -> Self {
+        Self {
+            inner,
+            minifier: InternalMinifier::new(stream),
+            input: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            pos_output: 0,
+            finished: false,
+            error: None,
+        }
+    }
+
+    /// Replace invalid utf8 in the input by `U+FFFD` instead of returning an
+    /// error (default: `false`)
+    #[must_use]
+    pub const fn lossy(mut self, lossy: bool) -> Self {
+        self.minifier.set_lossy(lossy);
+        self
+    }
+}
+
+impl<R: Unpin, S: Stream> InternalAsyncReader<R, S> {
+    /// Copies minified output into `buf`, `poll_inner` is used to read more
+    /// input while there is no output
+    fn poll_minified(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+        mut poll_inner: impl FnMut(Pin<&mut R>, &mut Context<'_>, &mut [u8]) -> Poll<Result<usize>>,
+    ) -> Poll<Result<usize>> {
+        loop {
+            let output = &self.minifier.output().as_bytes()[self.pos_output..];
+            if !output.is_empty() {
+                let len = output.len().min(buf.len());
+                buf[..len].copy_from_slice(&output[..len]);
+                self.pos_output += len;
+                if self.pos_output == self.minifier.output().len() {
+                    self.minifier.clear_output();
+                    self.pos_output = 0;
+                }
+                return Poll::Ready(Ok(len));
+            }
+            if let Some(error) = self.error.take() {
+                return Poll::Ready(Err(error));
+            }
+            if self.finished || buf.is_empty() {
+                return Poll::Ready(Ok(0));
+            }
+            let read = ready!(poll_inner(Pin::new(&mut self.inner), cx, &mut self.input))?;
+            if let Err(error) = self.feed(read) {
+                // like the blocking reader, the input ends with an error, which
+                // is returned after the output before it
+                self.finished = true;
+                self.error = Some(error);
+                let _ = self.minifier.end();
+            }
+        }
+    }
+
+    fn feed(&mut self, read: usize) -> Result<()> {
+        if read == 0 {
+            self.finished = true;
+            return self.minifier.end();
+        }
+        let mut rest = &self.input[..read];
+        while !rest.is_empty() {
+            let taken = self.minifier.push_bytes(rest)?;
+            rest = &rest[taken..];
+        }
+        Ok(())
+    }
+}
+
+impl<R: fmt::Debug, S: fmt::Debug> fmt::Debug for InternalAsyncReader<R, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("InternalAsyncReader")
+            .field("inner", &self.inner)
+            .field("minifier", &self.minifier)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+pub struct InternalAsyncWriter<W, S> {
+    inner: W,
+    minifier: InternalMinifier<S>,
+    /// Number of bytes of the minified output which are already written
+    pos_output: usize,
+    finished: bool,
+}
+
+impl<W, S: Stream> InternalAsyncWriter<W, S> {
+    pub const fn new(inner: W, stream: S) -> Self {
+        Self {
+            inner,
+            minifier: InternalMinifier::new(stream),
+            pos_output: 0,
+            finished: false,
+        }
+    }
+
+    /// Returns a reference to the underlying writer
+    pub const fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the underlying writer. Output which is not written yet is
+    /// lost, so the writer should be shut down or closed first.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Unpin, S: Stream> InternalAsyncWriter<W, S> {
+    /// Writes the minified output to the underlying writer using
+    /// `poll_inner`
+    fn poll_output(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut poll_inner: impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<Result<usize>>,
+    ) -> Poll<Result<()>> {
+        while self.pos_output < self.minifier.output().len() {
+            let output = &self.minifier.output().as_bytes()[self.pos_output..];
+            let written = ready!(poll_inner(Pin::new(&mut self.inner), cx, output))?;
+            if written == 0 {
+                return Poll::Ready(Err(Error::from(ErrorKind::WriteZero)));
+            }
+            self.pos_output += written;
+        }
+        self.minifier.clear_output();
+        self.pos_output = 0;
+        Poll::Ready(Ok(()))
+    }
+
+    /// Minifies `buf` once the output of the previous call is written
+    fn poll_minify(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        poll_inner: impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<Result<usize>>,
+    ) -> Poll<Result<usize>> {
+        ready!(self.poll_output(cx, poll_inner))?;
+        Poll::Ready(self.minifier.push_bytes(buf))
+    }
+
+    /// Ends the input once and writes the remaining output
+    fn poll_finish(
+        &mut self,
+        cx: &mut Context<'_>,
+        poll_inner: impl FnMut(Pin<&mut W>, &mut Context<'_>, &[u8]) -> Poll<Result<usize>>,
+    ) -> Poll<Result<()>> {
+        if !self.finished {
+            self.finished = true;
+            self.minifier.end()?;
+        }
+        self.poll_output(cx, poll_inner)
+    }
+}
+
+impl<W: fmt::Debug, S: fmt::Debug> fmt::Debug for InternalAsyncWriter<W, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("InternalAsyncWriter")
+            .field("inner", &self.inner)
+            .field("minifier", &self.minifier)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_impl {
+    use super::{InternalAsyncReader, InternalAsyncWriter};
+    use crate::io::stream::Stream;
+    use std::{
+        io::Result,
+        pin::Pin,
+        task::{ready, Context, Poll},
+    };
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    impl<R: AsyncRead + Unpin, S: Stream + Unpin> AsyncRead for InternalAsyncReader<R, S> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<Result<()>> {
+            let read = ready!(self.get_mut().poll_minified(
+                cx,
+                buf.initialize_unfilled(),
+                |inner, cx, input| {
+                    let mut input = ReadBuf::new(input);
+                    inner
+                        .poll_read(cx, &mut input)
+                        .map_ok(|()| input.filled().len())
+                }
+            ))?;
+            buf.advance(read);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl<W: AsyncWrite + Unpin, S: Stream + Unpin> AsyncWrite for InternalAsyncWriter<W, S> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<Result<usize>> {
+            self.get_mut().poll_minify(cx, buf, AsyncWrite::poll_write)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+            let this = self.get_mut();
+            ready!(this.poll_output(cx, AsyncWrite::poll_write))?;
+            Pin::new(&mut this.inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+            let this = self.get_mut();
+            ready!(this.poll_finish(cx, AsyncWrite::poll_write))?;
+            Pin::new(&mut this.inner).poll_shutdown(cx)
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+mod futures_impl {
+    use super::{InternalAsyncReader, InternalAsyncWriter};
+    use crate::io::stream::Stream;
+    use futures_io::{AsyncRead, AsyncWrite};
+    use std::{
+        io::Result,
+        pin::Pin,
+        task::{ready, Context, Poll},
+    };
+
+    impl<R: AsyncRead + Unpin, S: Stream + Unpin> AsyncRead for InternalAsyncReader<R, S> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<Result<usize>> {
+            self.get_mut().poll_minified(cx, buf, AsyncRead::poll_read)
+        }
+    }
+
+    impl<W: AsyncWrite + Unpin, S: Stream + Unpin> AsyncWrite for InternalAsyncWriter<W, S> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<Result<usize>> {
+            self.get_mut().poll_minify(cx, buf, AsyncWrite::poll_write)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+            let this = self.get_mut();
+            ready!(this.poll_output(cx, AsyncWrite::poll_write))?;
+            Pin::new(&mut this.inner).poll_flush(cx)
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+            let this = self.get_mut();
+            ready!(this.poll_finish(cx, AsyncWrite::poll_write))?;
+            Pin::new(&mut this.inner).poll_close(cx)
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use crate::io::{stream::Stream, unstable::CharsError};
+use std::{
+    fmt::{self, Formatter},
+    io::{Error, Result},
+    mem,
+    str::from_utf8,
+    string::Drain,
+};
+
+pub struct InternalMinifier<S> {
+    stream: S,
+    /// Minified output which is not returned yet
+    buffer: String,
+    /// Bytes of a character which was not fed completely
+    pending: [u8; 4],
+    len_pending: usize,
+    /// Byte offset of the next fed byte in the input
+    position: usize,
+    /// Replace invalid utf8 by `U+FFFD` instead of returning an error
+    lossy: bool,
+}
+
+impl<S: Stream> InternalMinifier<S> {
+    pub const fn new(stream: S) -> Self {
+        Self {
+            stream,
+            buffer: String::new(),
+            pending: [0; 4],
+            len_pending: 0,
+            position: 0,
+            lossy: false,
+        }
+    }
+
+    #[cfg(any(feature = "tokio", feature = "futures"))]
+    pub(crate) const fn set_lossy(&mut self, lossy: bool) {
+        self.lossy = lossy;
+    }
+
+    /// Minifies the next chunk of the input and returns the minified output
+    /// which is known so far
+    ///
+    /// Output which depends on the following input is kept until the next
+    /// call or [`finish`](Self::finish).
+    pub fn feed(&mut self, input: &str) -> Drain<'_> {
+        self.push_str(input);
+        self.buffer.drain(..)
+    }
+
+    /// Minifies the next chunk of the utf8 encoded input and appends the
+    /// minified output which is known so far to `out`
+    ///
+    /// Characters may be split between chunks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with a [`CharsError`] as inner error if the input is
+    /// not valid utf8. The output before the invalid character is still
+    /// appended to `out`.
+    pub fn feed_bytes(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        let mut rest = input;
+        while !rest.is_empty() {
+            let result = self.push_bytes(rest);
+            out.extend_from_slice(self.buffer.as_bytes());
+            self.buffer.clear();
+            rest = &rest[result?..];
+        }
+        Ok(())
+    }
+
+    /// Ends the input and returns the remaining minified output
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with a [`CharsError`] as inner error if the input
+    /// ended in the middle of a character.
+    pub fn finish(mut self) -> Result<String> {
+        self.end()?;
+        Ok(mem::take(&mut self.buffer))
+    }
+
+    /// Returns the minified output which is not taken yet
+    pub(crate) fn output(&self) -> &str {
+        &self.buffer
+    }
+
+    pub(crate) fn clear_output(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Ends the input and writes the remaining minified output into the
+    /// output buffer
+    pub(crate) fn end(&mut self) -> Result<()> {
+        if self.len_pending > 0 {
+            self.len_pending = 0;
+            if !self.lossy {
+                return Err(not_utf8(self.position));
+            }
+            self.push_str(&char::REPLACEMENT_CHARACTER.to_string());
+        }
+        self.stream.finish(&mut self.buffer);
+        Ok(())
+    }
+
+    /// Feeds `U+FFFD` for `len` invalid bytes of the input
+    fn push_replacement(&mut self, len: usize) {
+        self.stream
+            .push(char::REPLACEMENT_CHARACTER, &mut self.buffer);
+        self.position += len;
+    }
+
+    fn push_str(&mut self, input: &str) {
+        for item in input.chars() {
+            self.stream.push(item, &mut self.buffer);
+        }
+        self.position += input.len();
+    }
+
+    /// Feeds the valid utf8 at the start of `input` and returns the number
+    /// of bytes taken. Bytes of an incomplete character at the end are kept
+    /// until the next call.
+    pub(crate) fn push_bytes(&mut self, input: &[u8]) -> Result<usize> {
+        let taken = if self.len_pending > 0 {
+            self.complete_pending(input)?
+        } else {
+            0
+        };
+        let rest = &input[taken..];
+        match from_utf8(rest) {
+            Ok(input) => self.push_str(input),
+            Err(error) => {
+                let (valid, invalid) = rest.split_at(error.valid_up_to());
+                // `valid` contains valid utf8 only
+                self.push_str(from_utf8(valid).unwrap_or_default());
+                if let Some(len) = error.error_len() {
+                    if self.lossy {
+                        self.push_replacement(len);
+                        return Ok(taken + valid.len() + len);
+                    }
+                    // the invalid bytes are rejected by the next call
+                    if taken + valid.len() == 0 {
+                        return Err(not_utf8(self.position));
+                    }
+                    return Ok(taken + valid.len());
+                }
+                self.pending[..invalid.len()].copy_from_slice(invalid);
+                self.len_pending = invalid.len();
+            }
+        }
+        Ok(input.len())
+    }
+
+    /// Completes the pending character with bytes from the start of `input`,
+    /// returns the number of bytes taken from `input`
+    fn complete_pending(&mut self, input: &[u8]) -> Result<usize> {
+        let width = match self.pending[0] {
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            _ => 4,
+        };
+        let mut taken = (width - self.len_pending).min(input.len());
+        if self.lossy {
+            // a byte which does not continue the character starts the next one
+            let continued = input[..taken]
+                .iter()
+                .take_while(|&&byte| byte & 0xc0 == 0x80);
+            taken = continued.count();
+        }
+        self.pending[self.len_pending..self.len_pending + taken].copy_from_slice(&input[..taken]);
+        self.len_pending += taken;
+        if self.len_pending < width && taken == input.len() {
+            return Ok(taken);
+        }
+        let len = mem::take(&mut self.len_pending);
+        let pending = self.pending;
+        match from_utf8(&pending[..len]) {
+            Ok(item) => self.push_str(item),
+            Err(_) if self.lossy => self.push_replacement(len),
+            Err(_) => return Err(not_utf8(self.position)),
+        }
+        Ok(taken)
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for InternalMinifier<S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("InternalMinifier")
+            .field("stream", &self.stream)
+            .field("buffer", &self.buffer)
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+fn not_utf8(offset: usize) -> Error {
+    Error::from(CharsError::NotUtf8 { offset })
+}
@@ -1,3 +1,8 @@
+#[cfg(any(feature = "tokio", feature = "futures"))]
+pub mod async_io;
+pub mod minifier;
 pub mod multi_filter;
 pub mod reader;
+pub mod stream;
 pub mod unstable;
+pub mod writer;
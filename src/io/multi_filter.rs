@@ -1,89 +1,291 @@
-use std::{fmt, mem};
+use crate::io::stream::Stream;
+use std::{borrow::Cow, fmt};
 
-pub struct MultiFilter<I: Iterator, P, M> {
+/// What happens with a character of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// The character is written to the output
+    Keep,
+    /// The character is removed from the output
+    Drop,
+    /// The character is replaced by the given, possibly empty, string
+    Replace(Cow<'static, str>),
+}
+
+impl From<bool> for Action {
+    /// Keeps the character for `true`, drops it for `false`
+    #[inline]
+    fn from(keep: bool) -> Self {
+        if keep {
+            Self::Keep
+        } else {
+            Self::Drop
+        }
+    }
+}
+
+/// Ring buffer of the last `N` items, which are kept twice so that they can
+/// be viewed in order as one slice
+struct Lookahead<T, const N: usize> {
+    items: [[Option<T>; N]; 2],
+    head: usize,
+}
+
+impl<T: Copy, const N: usize> Lookahead<T, N> {
+    #[inline]
+    const fn new() -> Self {
+        Self {
+            items: [[None; N]; 2],
+            head: 0,
+        }
+    }
+
+    /// Appends `item` and returns the oldest item, which drops out
+    #[inline]
+    const fn shift(&mut self, item: Option<T>) -> Option<T> {
+        if N == 0 {
+            return item;
+        }
+        let oldest = self.items[0][self.head];
+        self.items[0][self.head] = item;
+        self.items[1][self.head] = item;
+        self.head = (self.head + 1) % N;
+        oldest
+    }
+
+    /// Returns the items from the oldest to the newest
+    #[inline]
+    fn as_slice(&self) -> &[Option<T>] {
+        &self.items.as_flattened()[self.head..self.head + N]
+    }
+}
+
+impl<T: Copy + fmt::Debug, const N: usize> fmt::Debug for Lookahead<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+/// Filter which passes every character together with the `N` characters
+/// following it to the predicate, whose [`Action`] decides on the output
+pub struct MultiFilter<I, P, M, const N: usize> {
     minifier: M,
     iter: I,
     predicate: P,
     initialized: bool,
-    item1: Option<I::Item>,
-    item2: Option<I::Item>,
-    item3: Option<I::Item>,
-    item4: Option<I::Item>,
-    item5: Option<I::Item>,
+    lookahead: Lookahead<char, N>,
+    /// Replacement which is not returned yet
+    buffer: String,
+    pos_buffer: usize,
 }
 
-impl<I: Iterator, P, M: Default> MultiFilter<I, P, M> {
+impl<I, P, M: Default, const N: usize> MultiFilter<I, P, M, N> {
     #[inline]
     pub fn new(iter: I, predicate: P) -> Self {
+        Self::with_minifier(iter, predicate, M::default())
+    }
+}
+
+impl<I, P, M, const N: usize> MultiFilter<I, P, M, N> {
+    #[inline]
+    pub const fn with_minifier(iter: I, predicate: P, minifier: M) -> Self {
         Self {
-            minifier: M::default(),
+            minifier,
             iter,
             predicate,
             initialized: false,
-            item1: None,
-            item2: None,
-            item3: None,
-            item4: None,
-            item5: None,
+            lookahead: Lookahead::new(),
+            buffer: String::new(),
+            pos_buffer: 0,
         }
     }
 }
 
-impl<I: Iterator + fmt::Debug, P, M> fmt::Debug for MultiFilter<I, P, M> {
+impl<I: fmt::Debug, P, M, const N: usize> fmt::Debug for MultiFilter<I, P, M, N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Filter")
             .field("iter", &self.iter)
             .field("initialized", &self.initialized)
-            .finish()
+            .finish_non_exhaustive()
     }
 }
 
-impl<I, It, P, M> Iterator for MultiFilter<I, P, M>
+impl<I, P, M, const N: usize> Iterator for MultiFilter<I, P, M, N>
 where
-    I: Iterator<Item = It>,
-    It: Copy,
-    P: FnMut(
-        &mut M,
-        I::Item,
-        Option<I::Item>,
-        Option<I::Item>,
-        Option<I::Item>,
-        Option<I::Item>,
-        Option<I::Item>,
-    ) -> bool,
+    I: Iterator<Item = char>,
+    P: FnMut(&mut M, char, &[Option<char>]) -> Action,
 {
-    type Item = I::Item;
+    type Item = char;
 
     #[inline]
-    fn next(&mut self) -> Option<I::Item> {
+    fn next(&mut self) -> Option<char> {
         if !self.initialized {
-            self.item1 = self.iter.next();
-            self.item2 = self.iter.next();
-            self.item3 = self.iter.next();
-            self.item4 = self.iter.next();
-            self.item5 = self.iter.next();
+            for _ in 0..N {
+                let _ = self.lookahead.shift(self.iter.next());
+            }
             self.initialized = true;
         }
 
-        while let Some(item) = self.item1.take() {
-            mem::swap(&mut self.item1, &mut self.item2);
-            mem::swap(&mut self.item2, &mut self.item3);
-            mem::swap(&mut self.item3, &mut self.item4);
-            mem::swap(&mut self.item4, &mut self.item5);
-            self.item5 = self.iter.next();
-
-            if (self.predicate)(
-                &mut self.minifier,
-                item,
-                self.item1,
-                self.item2,
-                self.item3,
-                self.item4,
-                self.item5,
-            ) {
+        loop {
+            if let Some(item) = self.buffer[self.pos_buffer..].chars().next() {
+                self.pos_buffer += item.len_utf8();
                 return Some(item);
             }
+            self.buffer.clear();
+            self.pos_buffer = 0;
+
+            let item = self.lookahead.shift(self.iter.next())?;
+            match (self.predicate)(&mut self.minifier, item, self.lookahead.as_slice()) {
+                Action::Keep => return Some(item),
+                Action::Drop => {}
+                Action::Replace(replacement) => self.buffer.push_str(&replacement),
+            }
+        }
+    }
+}
+
+/// Push based counterpart of [`MultiFilter`] for characters, which holds
+/// back the last `N` characters as lookahead of the predicate
+pub struct MultiStream<P, M, const N: usize> {
+    minifier: M,
+    predicate: P,
+    lookahead: Lookahead<char, N>,
+}
+
+impl<P, M, const N: usize> MultiStream<P, M, N> {
+    #[inline]
+    pub const fn with_minifier(predicate: P, minifier: M) -> Self {
+        Self {
+            minifier,
+            predicate,
+            lookahead: Lookahead::new(),
+        }
+    }
+}
+
+impl<P, M: fmt::Debug, const N: usize> fmt::Debug for MultiStream<P, M, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MultiStream")
+            .field("minifier", &self.minifier)
+            .field("lookahead", &self.lookahead)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, M, const N: usize> MultiStream<P, M, N>
+where
+    P: FnMut(&mut M, char, &[Option<char>]) -> Action,
+{
+    /// Appends `item` to the lookahead and writes the output of the
+    /// character dropping out of it
+    fn shift(&mut self, item: Option<char>, out: &mut String) {
+        let Some(item) = self.lookahead.shift(item) else {
+            return;
+        };
+        match (self.predicate)(&mut self.minifier, item, self.lookahead.as_slice()) {
+            Action::Keep => out.push(item),
+            Action::Drop => {}
+            Action::Replace(replacement) => out.push_str(&replacement),
+        }
+    }
+}
+
+impl<P, M, const N: usize> Stream for MultiStream<P, M, N>
+where
+    P: FnMut(&mut M, char, &[Option<char>]) -> Action,
+{
+    fn push(&mut self, item: char, out: &mut String) {
+        self.shift(Some(item), out);
+    }
+
+    fn finish(&mut self, out: &mut String) {
+        for _ in 0..N {
+            self.shift(None, out);
         }
-        None
     }
 }
+
+#[cfg(test)]
+type Windows = Vec<(char, Vec<Option<char>>)>;
+
+/// Collects every item together with its lookahead
+#[cfg(test)]
+fn collect_windows(windows: &mut Windows, item: char, lookahead: &[Option<char>]) -> Action {
+    windows.push((item, lookahead.to_vec()));
+    Action::Keep
+}
+
+/// Doubles vowels, drops spaces and replaces newlines by a space
+#[cfg(test)]
+fn replace_characters(_: &mut (), item: char, _: &[Option<char>]) -> Action {
+    match item {
+        'a' | 'e' | 'i' | 'o' | 'u' => Action::Replace(item.to_string().repeat(2).into()),
+        ' ' => Action::Replace("".into()),
+        '\n' => Action::Replace(" ".into()),
+        _ => Action::Keep,
+    }
+}
+
+#[test]
+fn filter_with_lookahead() {
+    let mut filter: MultiFilter<_, _, Windows, 2> =
+        MultiFilter::new("abc".chars(), collect_windows);
+    assert_eq!(filter.by_ref().collect::<String>(), "abc");
+    let expected = vec![
+        ('a', vec![Some('b'), Some('c')]),
+        ('b', vec![Some('c'), None]),
+        ('c', vec![None, None]),
+    ];
+    assert_eq!(filter.minifier, expected);
+}
+
+#[test]
+fn filter_with_lookahead_longer_than_input() {
+    let mut filter: MultiFilter<_, _, Windows, 9> = MultiFilter::new("ab".chars(), collect_windows);
+    assert_eq!(filter.by_ref().collect::<String>(), "ab");
+    assert_eq!(filter.minifier[0].1[..2], [Some('b'), None]);
+    assert_eq!(filter.minifier[1].1, vec![None; 9]);
+}
+
+#[test]
+fn filter_without_lookahead() {
+    let mut filter: MultiFilter<_, _, Windows, 0> = MultiFilter::new("ab".chars(), collect_windows);
+    assert_eq!(filter.by_ref().collect::<String>(), "ab");
+    assert_eq!(filter.minifier, vec![('a', vec![]), ('b', vec![])]);
+}
+
+#[test]
+fn stream_with_lookahead() {
+    let mut stream: MultiStream<_, Windows, 2> =
+        MultiStream::with_minifier(collect_windows, Vec::new());
+    let mut out = String::new();
+    for item in "abc".chars() {
+        stream.push(item, &mut out);
+    }
+    assert_eq!(out, "a");
+    stream.finish(&mut out);
+    assert_eq!(out, "abc");
+    let expected = vec![
+        ('a', vec![Some('b'), Some('c')]),
+        ('b', vec![Some('c'), None]),
+        ('c', vec![None, None]),
+    ];
+    assert_eq!(stream.minifier, expected);
+}
+
+#[test]
+fn filter_with_replacement() {
+    let filter: MultiFilter<_, _, (), 1> = MultiFilter::new("a b\ncd".chars(), replace_characters);
+    assert_eq!(filter.collect::<String>(), "aab cd");
+}
+
+#[test]
+fn stream_with_replacement() {
+    let mut stream: MultiStream<_, (), 1> = MultiStream::with_minifier(replace_characters, ());
+    let mut out = String::new();
+    for item in "a b\ncd".chars() {
+        stream.push(item, &mut out);
+    }
+    stream.finish(&mut out);
+    assert_eq!(out, "aab cd");
+}
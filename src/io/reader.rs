@@ -1,112 +1,172 @@
-use crate::io::{
-    multi_filter::MultiFilter,
-    unstable::{Chars, CharsError},
-};
+use crate::io::unstable::{Chars, CharsError};
 use std::{
     fmt::{self, Formatter},
-    io::{Read, Result},
-    iter::{FilterMap, Iterator},
-    result,
+    io::{Error, Read, Result},
+    iter::Iterator,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
-pub type Filter<R> = FilterMap<Chars<R>, fn(result::Result<char, CharsError>) -> Option<char>>;
-
-pub struct InternalReader<R: Read, P, M> {
-    iter: MultiFilter<Filter<R>, P, M>,
-    bytes: Option<Vec<u8>>,
-    pos_bytes: usize,
+/// State shared between the [`Filter`] at the start of a reader and the
+/// [`InternalReader`] at its end
+#[derive(Debug, Default)]
+pub struct Source {
+    lossy: AtomicBool,
+    error: Mutex<Option<Error>>,
 }
 
-impl<R: Read, P, M: Default> InternalReader<R, P, M> {
-    pub fn new(inner_reader: R, predicate: P) -> Self {
-        Self {
-            iter: MultiFilter::new(
-                Chars {
-                    inner: inner_reader,
-                }
-                .filter_map(Self::filter_map_result_error),
-                predicate,
-            ),
-            bytes: None,
-            pos_bytes: 0,
+impl Source {
+    /// Leaves the error which ended the input, unless an earlier error did
+    pub fn fail(&self, error: Error) {
+        if let Ok(mut slot) = self.error.lock() {
+            let _ = slot.get_or_insert(error);
         }
     }
 
-    #[allow(clippy::needless_pass_by_value)]
-    fn filter_map_result_error(result: result::Result<char, CharsError>) -> Option<char> {
-        match result {
-            Ok(e) => Some(e),
-            _ => None,
-        }
+    /// Takes the error which ended the input, if there is one
+    fn take_error(&self) -> Option<Error> {
+        self.error.lock().ok()?.take()
     }
+}
 
-    fn handle_bytes(
-        &mut self,
-        bytes: Vec<u8>,
-        bytes_start: usize,
-        buf: &mut [u8],
-        buf_start: usize,
-    ) -> usize {
-        let mut pos_bytes = bytes_start;
-        let mut pos_buf = buf_start;
+/// Iterator over the characters of a [`Read`], which ends at the first
+/// error and leaves it in its [`Source`]
+#[derive(Debug)]
+pub struct Filter<R> {
+    chars: Chars<R>,
+    source: Arc<Source>,
+    failed: bool,
+}
 
-        for item in buf.iter_mut().skip(buf_start) {
-            if pos_bytes < bytes.len() {
-                *item = bytes[pos_bytes];
-                pos_bytes += 1;
-                pos_buf += 1;
-            } else {
-                break;
+impl<R> Filter<R> {
+    /// Returns the [`Source`] shared with the reader, which filters after
+    /// this one use to end the input with an error
+    pub fn source(&self) -> Arc<Source> {
+        Arc::clone(&self.source)
+    }
+}
+
+impl<R: Read> Iterator for Filter<R> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        if self.failed {
+            return None;
+        }
+        match self.chars.next()? {
+            Ok(item) => Some(item),
+            Err(CharsError::NotUtf8 { .. }) if self.source.lossy.load(Ordering::Relaxed) => {
+                Some(char::REPLACEMENT_CHARACTER)
+            }
+            Err(error) => {
+                self.failed = true;
+                self.source.fail(Error::from(error));
+                None
             }
         }
-        if pos_bytes < bytes.len() {
-            self.pos_bytes = pos_bytes;
-            self.bytes = Some(bytes);
+    }
+}
+
+pub struct InternalReader<I> {
+    iter: I,
+    source: Arc<Source>,
+    /// Bytes of a character which did not fit into the last buffer
+    pending: [u8; 4],
+    pos_pending: usize,
+    len_pending: usize,
+}
+
+impl<I> InternalReader<I> {
+    /// Creates a reader for `inner_reader`, whose characters are minified by
+    /// the iterator returned by `filter`
+    pub fn from_read<R: Read>(inner_reader: R, filter: impl FnOnce(Filter<R>) -> I) -> Self {
+        let source = Arc::new(Source::default());
+        let chars = Filter {
+            chars: Chars::new(inner_reader),
+            source: Arc::clone(&source),
+            failed: false,
+        };
+        Self {
+            iter: filter(chars),
+            source,
+            pending: [0; 4],
+            pos_pending: 0,
+            len_pending: 0,
         }
-        pos_buf
+    }
+
+    /// Replace invalid utf8 in the input by `U+FFFD` instead of returning an
+    /// error (default: `false`)
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// extern crate minify;
+    /// use std::io::Read;
+    /// use minify::html::minify_from_read;
+    ///
+    /// fn main() {
+    ///     let input: &[u8] = b"<p>  a \xff  </p>";
+    ///     let mut html_minified = String::new();
+    ///     minify_from_read(input)
+    ///         .lossy(true)
+    ///         .read_to_string(&mut html_minified)
+    ///         .expect("error at read");
+    ///     assert_eq!(html_minified, "<p> a \u{fffd} </p>");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn lossy(self, lossy: bool) -> Self {
+        self.source.lossy.store(lossy, Ordering::Relaxed);
+        self
     }
 }
 
-impl<R: Read + fmt::Debug, P, M> fmt::Debug for InternalReader<R, P, M> {
+impl<I: fmt::Debug> fmt::Debug for InternalReader<I> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_struct("InternalReader")
             .field("iter", &self.iter)
-            .field("bytes", &self.bytes)
-            .field("pos_bytes", &self.pos_bytes)
-            .finish()
+            .field("source", &self.source)
+            .finish_non_exhaustive()
     }
 }
 
-impl<R, P, M> Read for InternalReader<R, P, M>
+impl<I> Read for InternalReader<I>
 where
-    R: Read,
-    P: FnMut(
-        &mut M,
-        char,
-        Option<char>,
-        Option<char>,
-        Option<char>,
-        Option<char>,
-        Option<char>,
-    ) -> bool,
-    M: Default,
+    I: Iterator<Item = char>,
 {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        let mut pos_buffer = 0;
-
-        if let Some(item) = self.bytes.take() {
-            let pos_bytes = self.pos_bytes;
-            pos_buffer = self.handle_bytes(item, pos_bytes, buf, 0);
-            if pos_buffer >= buf.len() {
-                return Ok(pos_buffer);
-            }
+        if buf.is_empty() {
+            return Ok(0);
         }
+        let pending = &self.pending[self.pos_pending..self.len_pending];
+        let mut pos_buffer = pending.len().min(buf.len());
+        buf[..pos_buffer].copy_from_slice(&pending[..pos_buffer]);
+        self.pos_pending += pos_buffer;
 
-        while let Some(item) = self.iter.next() {
-            let bytes = item.to_string().into_bytes();
-            pos_buffer = self.handle_bytes(bytes, 0, buf, pos_buffer);
-            if pos_buffer >= buf.len() {
+        while pos_buffer < buf.len() {
+            let Some(item) = self.iter.next() else {
                 break;
+            };
+            let rest = &mut buf[pos_buffer..];
+            if item.len_utf8() <= rest.len() {
+                pos_buffer += item.encode_utf8(rest).len();
+            } else {
+                let len = rest.len();
+                self.len_pending = item.encode_utf8(&mut self.pending).len();
+                rest.copy_from_slice(&self.pending[..len]);
+                self.pos_pending = len;
+                pos_buffer += len;
+            }
+        }
+        // output read before an error is returned first, the error on the
+        // next call
+        if pos_buffer == 0 {
+            if let Some(error) = self.source.take_error() {
+                return Err(error);
             }
         }
         Ok(pos_buffer)
@@ -0,0 +1,72 @@
+use std::fmt;
+
+/// A minifier which is fed one character at a time and writes its output
+/// into a buffer as soon as it is known
+pub trait Stream {
+    /// Feeds the next character of the input
+    fn push(&mut self, item: char, out: &mut String);
+
+    /// Signals the end of the input and writes everything still pending
+    fn finish(&mut self, out: &mut String);
+}
+
+pub struct StreamFilter<I, S> {
+    iter: I,
+    stream: S,
+    buffer: String,
+    pos_buffer: usize,
+    finished: bool,
+}
+
+impl<I, S> StreamFilter<I, S> {
+    #[inline]
+    pub const fn new(iter: I, stream: S) -> Self {
+        Self {
+            iter,
+            stream,
+            buffer: String::new(),
+            pos_buffer: 0,
+            finished: false,
+        }
+    }
+}
+
+impl<I: fmt::Debug, S: fmt::Debug> fmt::Debug for StreamFilter<I, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StreamFilter")
+            .field("iter", &self.iter)
+            .field("stream", &self.stream)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I, S> Iterator for StreamFilter<I, S>
+where
+    I: Iterator<Item = char>,
+    S: Stream,
+{
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        loop {
+            if let Some(item) = self.buffer[self.pos_buffer..].chars().next() {
+                self.pos_buffer += item.len_utf8();
+                return Some(item);
+            }
+            self.buffer.clear();
+            self.pos_buffer = 0;
+            // nothing is pushed once the stream is finished
+            if self.finished {
+                return None;
+            }
+            if let Some(item) = self.iter.next() {
+                self.stream.push(item, &mut self.buffer);
+            } else {
+                self.finished = true;
+                self.stream.finish(&mut self.buffer);
+            }
+        }
+    }
+}
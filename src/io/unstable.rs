@@ -3,61 +3,111 @@ use std::{error, fmt, result, str::from_utf8};
 
 pub type Result<T> = result::Result<T, Error>;
 
+/// Number of bytes read from the underlying reader at once
+const BUFFER_SIZE: usize = 8 * 1024;
+
 /// An iterator over the `char`s of a reader.
 ///
-/// This struct is generally created by calling [`chars`][chars] on a reader.
-/// Please see the documentation of `chars()` for more details.
-///
-/// [chars]: trait.Read.html#method.chars
+/// The reader is read in blocks of [`BUFFER_SIZE`] bytes, so it does not
+/// need to be buffered.
 #[derive(Debug)]
 pub struct Chars<R> {
-    pub inner: R,
+    inner: R,
+    buffer: Box<[u8]>,
+    /// Position of the next byte to decode in `buffer`
+    start: usize,
+    /// End of the bytes read into `buffer`
+    end: usize,
+    /// Byte offset of `buffer[start]` in the input
+    position: usize,
+}
+
+impl<R> Chars<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buffer: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            start: 0,
+            end: 0,
+            position: 0,
+        }
+    }
+
+    const fn consume(&mut self, len: usize) {
+        self.start += len;
+        self.position += len;
+    }
+}
+
+impl<R: Read> Chars<R> {
+    /// Moves the remaining bytes to the front of the buffer and reads more
+    /// behind them. Returns `false` at the end of the input.
+    fn fill(&mut self) -> Result<bool> {
+        if self.start > 0 {
+            self.buffer.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+        loop {
+            return match self.inner.read(&mut self.buffer[self.end..]) {
+                Ok(0) => Ok(false),
+                Ok(n) => {
+                    self.end += n;
+                    Ok(true)
+                }
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => Err(e),
+            };
+        }
+    }
 }
 
 impl<R: Read> Iterator for Chars<R> {
     type Item = result::Result<char, CharsError>;
 
     fn next(&mut self) -> Option<result::Result<char, CharsError>> {
-        let first_byte = match read_one_byte(&mut self.inner)? {
-            Ok(b) => b,
-            Err(e) => return Some(Err(CharsError::Other(e))),
-        };
+        let offset = self.position;
+        if self.start == self.end {
+            match self.fill() {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(error) => return Some(Err(CharsError::Other { offset, error })),
+            }
+        }
 
-        let width = utf8_char_width(first_byte);
-        if width == 1 {
-            return Some(Ok(first_byte as char));
+        let first_byte = self.buffer[self.start];
+        if first_byte.is_ascii() {
+            self.consume(1);
+            return Some(Ok(char::from(first_byte)));
         }
+        let width = utf8_char_width(first_byte);
         if width == 0 {
-            return Some(Err(CharsError::NotUtf8));
+            self.consume(1);
+            return Some(Err(CharsError::NotUtf8 { offset }));
         }
-        let mut buf = [first_byte, 0, 0, 0];
-        {
-            let mut start = 1;
-            while start < width {
-                match self.inner.read(&mut buf[start..width]) {
-                    Ok(0) => return Some(Err(CharsError::NotUtf8)),
-                    Ok(n) => start += n,
-                    Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
-                    Err(e) => return Some(Err(CharsError::Other(e))),
+        while self.end - self.start < width {
+            match self.fill() {
+                Ok(true) => {}
+                Ok(false) => {
+                    self.consume(self.end - self.start);
+                    return Some(Err(CharsError::NotUtf8 { offset }));
                 }
+                Err(error) => return Some(Err(CharsError::Other { offset, error })),
+            }
+        }
+        let decoded = from_utf8(&self.buffer[self.start..self.start + width])
+            .map(|s| s.chars().next().unwrap_or(char::REPLACEMENT_CHARACTER));
+        match decoded {
+            Ok(item) => {
+                self.consume(width);
+                Some(Ok(item))
+            }
+            Err(e) => {
+                // only the invalid part is skipped, like `String::from_utf8_lossy`
+                self.consume(e.error_len().unwrap_or(width).max(1));
+                Some(Err(CharsError::NotUtf8 { offset }))
             }
         }
-        Some(match from_utf8(&buf[..width]).ok() {
-            Some(s) => Ok(s.chars().next().unwrap()),
-            None => Err(CharsError::NotUtf8),
-        })
-    }
-}
-
-fn read_one_byte(reader: &mut dyn Read) -> Option<Result<u8>> {
-    let mut buf = [0];
-    loop {
-        return match reader.read(&mut buf) {
-            Ok(0) => None,
-            Ok(..) => Some(Ok(buf[0])),
-            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
-            Err(e) => Some(Err(e)),
-        };
     }
 }
 
@@ -85,23 +135,47 @@ static UTF8_CHAR_WIDTH: [u8; 256] = [
     4, 4, 4, 4, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, // 0xFF
 ];
 
-/// An enumeration of possible errors that can be generated from the `Chars`
-/// adapter.
+/// An enumeration of possible errors that can be generated while reading
+/// the input of a minification [`Reader`](crate::html::Reader).
+///
+/// Both variants carry the byte offset of the character which could not be
+/// read. The error is returned as the inner error of an [`Error`] by
+/// [`Read::read`].
 #[derive(Debug)]
 pub enum CharsError {
     /// Variant representing that the underlying stream was read successfully
     /// but it did not contain valid utf8 data.
-    NotUtf8,
+    NotUtf8 {
+        /// Byte offset of the invalid character
+        offset: usize,
+    },
 
     /// Variant representing that an I/O error occurred.
-    Other(Error),
+    Other {
+        /// Byte offset of the character which was read
+        offset: usize,
+        /// Error of the underlying reader
+        error: Error,
+    },
+}
+
+impl CharsError {
+    /// Returns the byte offset of the character which could not be read
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        match *self {
+            Self::NotUtf8 { offset } | Self::Other { offset, .. } => offset,
+        }
+    }
 }
 
 impl fmt::Display for CharsError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
-            Self::NotUtf8 => write!(f, "byte stream did not contain valid utf8"),
-            Self::Other(ref e) => e.fmt(f),
+            Self::NotUtf8 { offset } => {
+                write!(f, "byte stream did not contain valid utf8 at byte {offset}")
+            }
+            Self::Other { offset, ref error } => write!(f, "{error} at byte {offset}"),
         }
     }
 }
@@ -109,8 +183,18 @@ impl fmt::Display for CharsError {
 impl error::Error for CharsError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
-            Self::NotUtf8 => None,
-            Self::Other(ref e) => Some(e),
+            Self::NotUtf8 { .. } => None,
+            Self::Other { ref error, .. } => Some(error),
         }
     }
 }
+
+impl From<CharsError> for Error {
+    fn from(error: CharsError) -> Self {
+        let kind = match error {
+            CharsError::NotUtf8 { .. } => ErrorKind::InvalidData,
+            CharsError::Other { ref error, .. } => error.kind(),
+        };
+        Self::new(kind, error)
+    }
+}
@@ -0,0 +1,83 @@
+use crate::io::{minifier::InternalMinifier, stream::Stream};
+use std::{
+    fmt::{self, Formatter},
+    io::{Error, Result, Write},
+};
+
+pub struct InternalWriter<W: Write, S: Stream> {
+    inner: Option<W>,
+    minifier: InternalMinifier<S>,
+    finished: bool,
+}
+
+impl<W: Write, S: Stream> InternalWriter<W, S> {
+    pub const fn new(inner: W, stream: S) -> Self {
+        Self {
+            inner: Some(inner),
+            minifier: InternalMinifier::new(stream),
+            finished: false,
+        }
+    }
+
+    /// Ends the input, writes the remaining minified output and returns the
+    /// underlying writer
+    ///
+    /// Dropping the writer finishes it as well, but ignores errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input ended in the middle of a character or
+    /// if writing to the underlying writer failed.
+    pub fn finish(mut self) -> Result<W> {
+        self.finish_stream()?;
+        self.inner
+            .take()
+            .ok_or_else(|| Error::other("writer is already finished"))
+    }
+
+    fn finish_stream(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+        self.minifier.end()?;
+        self.flush()
+    }
+
+    fn write_output(&mut self) -> Result<()> {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.write_all(self.minifier.output().as_bytes())?;
+        }
+        self.minifier.clear_output();
+        Ok(())
+    }
+}
+
+impl<W: Write, S: Stream> Write for InternalWriter<W, S> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let taken = self.minifier.push_bytes(buf)?;
+        self.write_output()?;
+        Ok(taken)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.write_output()?;
+        self.inner.as_mut().map_or(Ok(()), Write::flush)
+    }
+}
+
+impl<W: Write, S: Stream> Drop for InternalWriter<W, S> {
+    fn drop(&mut self) {
+        let _ = self.finish_stream();
+    }
+}
+
+impl<W: Write + fmt::Debug, S: Stream + fmt::Debug> fmt::Debug for InternalWriter<W, S> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("InternalWriter")
+            .field("inner", &self.inner)
+            .field("minifier", &self.minifier)
+            .field("finished", &self.finished)
+            .finish()
+    }
+}
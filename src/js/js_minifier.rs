@@ -0,0 +1,309 @@
+use crate::io::stream::Stream;
+
+/// Keywords after which a slash starts a regular expression instead of a
+/// division
+pub const REGEX_KEYWORDS: [&str; 13] = [
+    "await",
+    "case",
+    "delete",
+    "do",
+    "else",
+    "in",
+    "instanceof",
+    "new",
+    "return",
+    "throw",
+    "typeof",
+    "void",
+    "yield",
+];
+
+/// Whitespace which is written once the next character is known
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Whitespace {
+    None,
+    Space,
+    LineBreak,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum State {
+    Normal,
+    Slash,
+    LineComment,
+    BlockComment,
+    BlockCommentStar,
+    String(char),
+    StringEscape(char),
+    Template,
+    TemplateEscape,
+    TemplateDollar,
+    Regex,
+    RegexEscape,
+    RegexClass,
+    RegexClassEscape,
+}
+
+#[derive(Debug, Clone)]
+pub struct JsMinifier {
+    state: State,
+    last: Option<char>,
+    /// Character written before `last`
+    before_last: Option<char>,
+    whitespace: Whitespace,
+    word: String,
+    brace_depth: usize,
+    template_depths: Vec<usize>,
+}
+
+impl Default for JsMinifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsMinifier {
+    pub const fn new() -> Self {
+        Self {
+            state: State::Normal,
+            last: None,
+            before_last: None,
+            whitespace: Whitespace::None,
+            word: String::new(),
+            brace_depth: 0,
+            template_depths: Vec::new(),
+        }
+    }
+
+    fn emit(&mut self, item: char, out: &mut String) {
+        out.push(item);
+        self.before_last = self.last;
+        self.last = Some(item);
+    }
+
+    fn add_whitespace(&mut self, whitespace: Whitespace) {
+        if self.whitespace != Whitespace::LineBreak {
+            self.whitespace = whitespace;
+        }
+    }
+
+    /// Writes the pending whitespace if it is required in front of `item`
+    fn write_whitespace(&mut self, item: char, out: &mut String) {
+        let whitespace = self.whitespace;
+        self.whitespace = Whitespace::None;
+        let Some(last) = self.last else {
+            return;
+        };
+        if whitespace == Whitespace::LineBreak && ends_statement(last) && begins_statement(item) {
+            self.emit('\n', out);
+        } else if whitespace != Whitespace::None && needs_space(last, item) {
+            self.emit(' ', out);
+        }
+    }
+
+    /// Checks whether a slash written now starts a regular expression
+    fn is_regex_start(&self) -> bool {
+        match self.last {
+            None => true,
+            Some(last) if is_identifier(last) => REGEX_KEYWORDS.contains(&self.word.as_str()),
+            // a slash after a postfix `++` or `--` is a division
+            Some(last @ ('+' | '-')) if self.before_last == Some(last) => false,
+            Some(last) => !matches!(last, ')' | ']' | '"' | '\'' | '`' | '/'),
+        }
+    }
+
+    fn push_normal(&mut self, item: char, out: &mut String) {
+        if is_line_terminator(item) {
+            self.add_whitespace(Whitespace::LineBreak);
+            return;
+        }
+        if item.is_whitespace() || item.eq(&'\u{feff}') {
+            self.add_whitespace(Whitespace::Space);
+            return;
+        }
+        if item.eq(&'/') {
+            self.state = State::Slash;
+            return;
+        }
+        self.push_significant(item, out);
+    }
+
+    fn push_significant(&mut self, item: char, out: &mut String) {
+        self.write_whitespace(item, out);
+        match item {
+            '"' | '\'' => self.state = State::String(item),
+            '`' => self.state = State::Template,
+            '{' => self.brace_depth += 1,
+            '}' => {
+                if self.template_depths.last() == Some(&self.brace_depth) {
+                    let _ = self.template_depths.pop();
+                    self.state = State::Template;
+                } else {
+                    self.brace_depth = self.brace_depth.saturating_sub(1);
+                }
+            }
+            _ => {}
+        }
+        if is_identifier(item) {
+            if !self.last.is_some_and(is_identifier) {
+                self.word.clear();
+            }
+            if self.word.len() <= "instanceof".len() {
+                self.word.push(item);
+            }
+        } else {
+            self.word.clear();
+        }
+        self.emit(item, out);
+    }
+}
+
+impl Stream for JsMinifier {
+    fn push(&mut self, item: char, out: &mut String) {
+        match self.state {
+            State::Normal => self.push_normal(item, out),
+            State::Slash => match item {
+                '/' => self.state = State::LineComment,
+                '*' => self.state = State::BlockComment,
+                _ => {
+                    let is_regex = self.is_regex_start();
+                    self.state = State::Normal;
+                    self.push_significant('/', out);
+                    if is_regex {
+                        self.state = State::Regex;
+                    }
+                    self.push(item, out);
+                }
+            },
+            State::LineComment => {
+                if is_line_terminator(item) {
+                    self.state = State::Normal;
+                    self.add_whitespace(Whitespace::LineBreak);
+                }
+            }
+            State::BlockComment => {
+                if item.eq(&'*') {
+                    self.state = State::BlockCommentStar;
+                } else if is_line_terminator(item) {
+                    self.add_whitespace(Whitespace::LineBreak);
+                }
+            }
+            State::BlockCommentStar => match item {
+                '/' => {
+                    self.state = State::Normal;
+                    self.add_whitespace(Whitespace::Space);
+                }
+                '*' => {}
+                _ => {
+                    self.state = State::BlockComment;
+                    self.push(item, out);
+                }
+            },
+            State::String(quote) => {
+                self.emit(item, out);
+                if item.eq(&'\\') {
+                    self.state = State::StringEscape(quote);
+                } else if item.eq(&quote) {
+                    self.state = State::Normal;
+                }
+            }
+            State::StringEscape(quote) => {
+                self.emit(item, out);
+                self.state = State::String(quote);
+            }
+            State::Template => {
+                self.emit(item, out);
+                match item {
+                    '\\' => self.state = State::TemplateEscape,
+                    '`' => self.state = State::Normal,
+                    '$' => self.state = State::TemplateDollar,
+                    _ => {}
+                }
+            }
+            State::TemplateEscape => {
+                self.emit(item, out);
+                self.state = State::Template;
+            }
+            State::TemplateDollar => {
+                if item.eq(&'{') {
+                    self.emit(item, out);
+                    self.template_depths.push(self.brace_depth);
+                    self.state = State::Normal;
+                } else {
+                    self.state = State::Template;
+                    self.push(item, out);
+                }
+            }
+            State::Regex => {
+                self.emit(item, out);
+                match item {
+                    '\\' => self.state = State::RegexEscape,
+                    '[' => self.state = State::RegexClass,
+                    '/' => self.state = State::Normal,
+                    _ => {}
+                }
+            }
+            State::RegexEscape => {
+                self.emit(item, out);
+                self.state = State::Regex;
+            }
+            State::RegexClass => {
+                self.emit(item, out);
+                match item {
+                    '\\' => self.state = State::RegexClassEscape,
+                    ']' => self.state = State::Regex,
+                    _ => {}
+                }
+            }
+            State::RegexClassEscape => {
+                self.emit(item, out);
+                self.state = State::RegexClass;
+            }
+        }
+    }
+
+    fn finish(&mut self, out: &mut String) {
+        if self.state == State::Slash {
+            self.state = State::Normal;
+            self.push_significant('/', out);
+        }
+        self.whitespace = Whitespace::None;
+    }
+}
+
+/// Checks whether a character can be part of an identifier, a number or a
+/// keyword
+pub fn is_identifier(item: char) -> bool {
+    item.is_alphanumeric()
+        || matches!(item, '$' | '_' | '\\')
+        || !item.is_ascii() && !item.is_whitespace()
+}
+
+pub const fn is_line_terminator(item: char) -> bool {
+    matches!(item, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+/// Checks whether a statement may end with the character, so that a removed
+/// line break could prevent automatic semicolon insertion
+fn ends_statement(item: char) -> bool {
+    is_identifier(item) || matches!(item, ')' | ']' | '}' | '"' | '\'' | '`' | '/' | '+' | '-')
+}
+
+/// Checks whether a statement may begin with the character
+fn begins_statement(item: char) -> bool {
+    is_identifier(item)
+        || matches!(
+            item,
+            '(' | '[' | '{' | '"' | '\'' | '`' | '/' | '+' | '-' | '!' | '~'
+        )
+}
+
+/// Checks whether a space is required between two characters to keep them
+/// from forming a single token or comment
+fn needs_space(last: char, item: char) -> bool {
+    is_identifier(last) && is_identifier(item)
+        || matches!(
+            (last, item),
+            ('0'..='9', '.') | ('+', '+') | ('-', '-' | '>') | ('/', '/' | '*') | ('<', '!' | '/')
+        )
+}
@@ -0,0 +1,45 @@
+/// Options for JavaScript minification
+///
+/// The default options apply the same rules as [`minify`](super::minify).
+/// Every setter consumes and returns the options, so they can be chained:
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::js::JsOptions;
+///
+/// fn main() {
+///     let options = JsOptions::new().mangle_locals(true);
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct JsOptions {
+    pub(crate) mangle_locals: bool,
+}
+
+impl Default for JsOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsOptions {
+    /// Creates the default options
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            mangle_locals: false,
+        }
+    }
+
+    /// Rename variables, parameters and functions declared inside of
+    /// functions to short names (default: `false`)
+    ///
+    /// `function f(value){return value*2}` => `function f(a){return a*2}`.
+    /// Globals, property names and every binding visible to `eval` or `with`
+    /// keep their names.
+    #[must_use]
+    pub const fn mangle_locals(mut self, mangle_locals: bool) -> Self {
+        self.mangle_locals = mangle_locals;
+        self
+    }
+}
@@ -0,0 +1,769 @@
+use crate::js::tokenizer::{tokenize, Token};
+use std::collections::{HashMap, HashSet};
+
+/// Words which are never renamed and never used as new names
+const RESERVED: [&str; 55] = [
+    "arguments",
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "from",
+    "function",
+    "get",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "of",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "set",
+    "static",
+    "super",
+    "switch",
+    "target",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Characters a generated name may start with
+const NAME_START: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Characters a generated name may continue with
+const NAME_PART: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Kind of the bracket a frame was opened with
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Context {
+    Block,
+    Object,
+    Class,
+    Paren,
+    Array,
+}
+
+/// Scope which receives a declared binding
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Kind {
+    /// The nearest function scope, for `var` and function declarations
+    Var,
+    /// The current scope, for `let`, `const`, classes and parameters
+    Let,
+}
+
+/// What is expected after the closing bracket of a frame
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum AfterClose {
+    None,
+    Body,
+    ForBody,
+}
+
+/// Meaning of the next opening bracket
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Pending {
+    Params,
+    Catch,
+    For,
+    Body,
+    Class(usize),
+}
+
+#[derive(Debug, Clone)]
+struct Frame {
+    context: Context,
+    /// Scopes which are closed together with the frame
+    scopes: usize,
+    /// Scopes of arrow functions whose expression body ends with the
+    /// current expression
+    expression_scopes: usize,
+    /// Scopes of `for` loops whose body ends with the current statement
+    statement_scopes: usize,
+    declaring: Option<Kind>,
+    pattern: Option<Kind>,
+    in_value: bool,
+    in_default: bool,
+    ternary: usize,
+    is_ternary_colon: bool,
+    after_close: AfterClose,
+}
+
+impl Frame {
+    const fn new(context: Context) -> Self {
+        Self {
+            context,
+            scopes: 0,
+            expression_scopes: 0,
+            statement_scopes: 0,
+            declaring: None,
+            pattern: None,
+            in_value: false,
+            in_default: false,
+            ternary: 0,
+            is_ternary_colon: false,
+            after_close: AfterClose::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Scope {
+    parent: Option<usize>,
+    is_function: bool,
+    /// Set if `eval` or `with` may access the bindings by their name
+    is_dynamic: bool,
+    bindings: Vec<String>,
+}
+
+/// An identifier which may be renamed
+#[derive(Debug, Copy, Clone)]
+struct Occurrence {
+    token: usize,
+    scope: usize,
+    is_declaration: bool,
+    is_shorthand: bool,
+}
+
+/// Renames local variables, parameters and functions of minified
+/// javascript to short names. Bindings of the global scope and of scopes
+/// which use `eval` or `with` keep their names.
+pub fn mangle(js: &str) -> String {
+    let tokens = tokenize(js);
+    let mut analyzer = Analyzer::new(&tokens);
+    for pos in 0..tokens.len() {
+        analyzer.handle(pos);
+    }
+    let names = analyzer.rename();
+    let mut out = String::with_capacity(js.len());
+    for (pos, token) in tokens.iter().enumerate() {
+        match names.get(&pos) {
+            Some(name) => out.push_str(name),
+            None => token.write(&mut out),
+        }
+    }
+    out
+}
+
+struct Analyzer<'a> {
+    tokens: &'a [Token],
+    scopes: Vec<Scope>,
+    scope_stack: Vec<usize>,
+    frames: Vec<Frame>,
+    pending: Option<Pending>,
+    skipped: HashSet<usize>,
+    occurrences: Vec<Occurrence>,
+}
+
+impl<'a> Analyzer<'a> {
+    fn new(tokens: &'a [Token]) -> Self {
+        Self {
+            tokens,
+            scopes: vec![Scope {
+                parent: None,
+                is_function: false,
+                is_dynamic: false,
+                bindings: Vec::new(),
+            }],
+            scope_stack: vec![0],
+            frames: vec![Frame::new(Context::Block)],
+            pending: None,
+            skipped: HashSet::new(),
+            occurrences: Vec::new(),
+        }
+    }
+
+    fn handle(&mut self, pos: usize) {
+        match &self.tokens[pos] {
+            Token::Identifier(word) => self.handle_identifier(pos, word),
+            Token::Punctuator(punctuator) => self.handle_punctuator(pos, punctuator),
+            Token::LineBreak => self.handle_line_break(pos),
+            Token::Literal(_) | Token::Space => {}
+        }
+    }
+
+    fn next(&self, pos: usize) -> Option<usize> {
+        (pos + 1..self.tokens.len())
+            .find(|next| !matches!(self.tokens[*next], Token::Space | Token::LineBreak))
+    }
+
+    fn prev(&self, pos: usize) -> Option<usize> {
+        (0..pos)
+            .rev()
+            .find(|prev| !matches!(self.tokens[*prev], Token::Space | Token::LineBreak))
+    }
+
+    fn is_at(&self, pos: Option<usize>, text: &str) -> bool {
+        pos.is_some_and(|pos| self.tokens[pos].is(text))
+    }
+
+    fn is_at_any(&self, pos: Option<usize>, texts: &[&str]) -> bool {
+        texts.iter().any(|text| self.is_at(pos, text))
+    }
+
+    fn has_line_break_before(&self, pos: usize) -> bool {
+        let start = self.prev(pos).map_or(0, |prev| prev + 1);
+        self.tokens[start..pos]
+            .iter()
+            .any(|token| token == &Token::LineBreak)
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        let last = self.frames.len() - 1;
+        &mut self.frames[last]
+    }
+
+    fn scope(&self) -> usize {
+        self.scope_stack.last().copied().unwrap_or_default()
+    }
+
+    fn push_scope(&mut self, is_function: bool) -> usize {
+        let id = self.scopes.len();
+        self.scopes.push(Scope {
+            parent: Some(self.scope()),
+            is_function,
+            is_dynamic: false,
+            bindings: Vec::new(),
+        });
+        self.scope_stack.push(id);
+        id
+    }
+
+    fn pop_scopes(&mut self, count: usize) {
+        for _ in 0..count {
+            if self.scope_stack.len() > 1 {
+                let _ = self.scope_stack.pop();
+            }
+        }
+    }
+
+    fn close_expression_scopes(&mut self) {
+        let count = self.frame().expression_scopes;
+        self.frame().expression_scopes = 0;
+        self.pop_scopes(count);
+    }
+
+    fn declare(&mut self, kind: Kind, pos: usize, is_shorthand: bool) {
+        let scope = match kind {
+            Kind::Var => self
+                .scope_stack
+                .iter()
+                .rev()
+                .copied()
+                .find(|scope| self.scopes[*scope].is_function)
+                .unwrap_or_default(),
+            Kind::Let => self.scope(),
+        };
+        self.declare_in(scope, pos, is_shorthand);
+    }
+
+    fn declare_in(&mut self, scope: usize, pos: usize, is_shorthand: bool) {
+        let Token::Identifier(name) = &self.tokens[pos] else {
+            return;
+        };
+        if RESERVED.contains(&name.as_str()) {
+            return;
+        }
+        let bindings = &mut self.scopes[scope].bindings;
+        if !bindings.contains(name) {
+            bindings.push(name.clone());
+        }
+        let _ = self.skipped.insert(pos);
+        self.occurrences.push(Occurrence {
+            token: pos,
+            scope,
+            is_declaration: true,
+            is_shorthand,
+        });
+    }
+
+    fn reference(&mut self, pos: usize, is_shorthand: bool) {
+        self.occurrences.push(Occurrence {
+            token: pos,
+            scope: self.scope(),
+            is_declaration: false,
+            is_shorthand,
+        });
+    }
+
+    /// Checks whether a function or class starting at the position is a
+    /// declaration instead of an expression
+    fn is_statement_start(&self, pos: usize) -> bool {
+        let prev = self.prev(pos);
+        if self.is_at(prev, "async") {
+            return prev.is_some_and(|prev| self.is_statement_start(prev));
+        }
+        prev.is_none()
+            || self.has_line_break_before(pos)
+            || self.is_at_any(prev, &[";", "{", "}", ")", "else", "export", "default"])
+    }
+
+    /// Returns where an identifier or pattern following `prev` is declared,
+    /// if it is in a binding position
+    fn binding_kind(&mut self, prev: Option<usize>) -> Option<Kind> {
+        let is_declaring_position = self.is_at_any(prev, &["var", "let", "const", ","]);
+        let is_list_position = self.is_at_any(prev, &["(", "[", ",", "..."]);
+        let is_object_position = self.is_at(prev, "...");
+        let is_value_position = self.is_at(prev, ":");
+        let frame = self.frame();
+        if frame.in_default {
+            return None;
+        }
+        if let Some(kind) = frame.declaring {
+            if is_declaring_position {
+                return Some(kind);
+            }
+        }
+        let kind = frame.pattern?;
+        let is_binding = match frame.context {
+            Context::Paren | Context::Array => is_list_position,
+            Context::Object => frame.in_value && is_value_position || is_object_position,
+            Context::Block | Context::Class => false,
+        };
+        is_binding.then_some(kind)
+    }
+
+    fn is_key_position(&self, prev: Option<usize>) -> bool {
+        self.is_at_any(prev, &["{", ",", "*", "get", "set", "async", "static"])
+    }
+
+    fn handle_identifier(&mut self, pos: usize, word: &str) {
+        let prev = self.prev(pos);
+        let next = self.next(pos);
+        if self.is_at_any(prev, &[".", "?."]) || self.skipped.contains(&pos) {
+            return;
+        }
+        if RESERVED.contains(&word) {
+            self.handle_keyword(pos, word, next);
+            return;
+        }
+        if self.is_at(next, "=>") {
+            let scope = self.push_scope(true);
+            self.declare_in(scope, pos, false);
+            return;
+        }
+        let is_key_position = self.is_key_position(prev);
+        let is_shorthand = self.is_at_any(next, &[",", "}", "="]);
+        let is_label = self.is_at(next, ":");
+        let frame = self.frame().clone();
+        match frame.context {
+            Context::Object if !frame.in_value && is_key_position => {
+                if is_shorthand {
+                    match frame.pattern {
+                        Some(kind) => self.declare(kind, pos, true),
+                        None => self.reference(pos, true),
+                    }
+                }
+                return;
+            }
+            Context::Class if !frame.in_value => return,
+            Context::Block if is_label && frame.ternary == 0 && frame.declaring.is_none() => return,
+            _ => {}
+        }
+        match self.binding_kind(prev) {
+            Some(kind) => self.declare(kind, pos, false),
+            None => self.reference(pos, false),
+        }
+    }
+
+    fn handle_keyword(&mut self, pos: usize, word: &str, next: Option<usize>) {
+        match word {
+            "var" | "let" | "const" => {
+                let kind = if word == "var" { Kind::Var } else { Kind::Let };
+                let frame = self.frame();
+                frame.declaring = Some(kind);
+                frame.in_default = false;
+            }
+            "function" => self.handle_function(pos),
+            "class" => self.handle_class(pos),
+            "catch" if self.is_at(next, "(") => self.pending = Some(Pending::Catch),
+            "for" => self.pending = Some(Pending::For),
+            "case" => self.frame().ternary += 1,
+            "eval" | "with" => {
+                for scope in &self.scope_stack {
+                    self.scopes[*scope].is_dynamic = true;
+                }
+            }
+            "in" | "of" => self.frame().declaring = None,
+            "break" | "continue" => {
+                if let Some(next) = next {
+                    if matches!(self.tokens[next], Token::Identifier(_))
+                        && !self.has_line_break_before(next)
+                    {
+                        let _ = self.skipped.insert(next);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_function(&mut self, pos: usize) {
+        let is_declaration = self.is_statement_start(pos);
+        let mut next = self.next(pos);
+        if self.is_at(next, "*") {
+            next = next.and_then(|next| self.next(next));
+        }
+        let name = next.filter(|next| matches!(self.tokens[*next], Token::Identifier(_)));
+        if let (Some(name), true) = (name, is_declaration) {
+            self.declare(Kind::Var, name, false);
+        }
+        let scope = self.push_scope(true);
+        if let (Some(name), false) = (name, is_declaration) {
+            self.declare_in(scope, name, false);
+        }
+        self.pending = Some(Pending::Params);
+    }
+
+    fn handle_class(&mut self, pos: usize) {
+        let next = self.next(pos);
+        if let Some(name) = next {
+            if matches!(&self.tokens[name], Token::Identifier(word) if word != "extends") {
+                if self.is_statement_start(pos) {
+                    self.declare(Kind::Let, name, false);
+                } else {
+                    let _ = self.skipped.insert(name);
+                }
+            }
+        }
+        self.pending = Some(Pending::Class(self.frames.len()));
+    }
+
+    /// Checks whether the token is an operator, unless it is one of `ends`
+    fn is_operator(&self, pos: Option<usize>, ends: &[&str]) -> bool {
+        match pos.map(|pos| &self.tokens[pos]) {
+            Some(Token::Punctuator(punctuator)) => !ends.contains(&punctuator.as_str()),
+            Some(Token::Identifier(word)) => matches!(word.as_str(), "in" | "instanceof"),
+            _ => false,
+        }
+    }
+
+    /// Checks whether the statement before a line break continues after it,
+    /// because an operator precedes or follows the line break
+    fn is_continued_after(&self, pos: usize) -> bool {
+        self.is_operator(self.prev(pos), &[")", "]", "}", ";", "++", "--"])
+            || self.is_operator(self.next(pos), &["{", "}", ";", "++", "--", "!", "~"])
+    }
+
+    fn handle_line_break(&mut self, pos: usize) {
+        let is_continued = self.is_continued_after(pos);
+        if !is_continued {
+            self.close_expression_scopes();
+            self.frame().declaring = None;
+        }
+        let frame = self.frame();
+        if frame.context == Context::Class {
+            frame.in_value = false;
+        }
+    }
+
+    fn handle_punctuator(&mut self, pos: usize, punctuator: &str) {
+        match punctuator {
+            "(" => self.open_paren(pos),
+            "[" => {
+                let mut frame = Frame::new(Context::Array);
+                frame.pattern = self.binding_kind(self.prev(pos));
+                self.frames.push(frame);
+            }
+            "{" => self.open_brace(pos),
+            ")" | "]" | "}" => self.close(pos),
+            "=>" => {
+                if self.is_at(self.next(pos), "{") {
+                    self.pending = Some(Pending::Body);
+                } else {
+                    self.frame().expression_scopes += 1;
+                }
+            }
+            "=" => {
+                let frame = self.frame();
+                frame.in_default = true;
+                if frame.context == Context::Class {
+                    frame.in_value = true;
+                }
+            }
+            "," => {
+                self.close_expression_scopes();
+                let frame = self.frame();
+                frame.in_default = false;
+                frame.in_value = false;
+            }
+            ";" => {
+                self.close_expression_scopes();
+                let count = self.frame().statement_scopes;
+                self.pop_scopes(count);
+                let context = self.frame().context;
+                *self.frame() = Frame {
+                    scopes: self.frame().scopes,
+                    after_close: self.frame().after_close,
+                    pattern: self.frame().pattern,
+                    ..Frame::new(context)
+                };
+            }
+            "?" => self.frame().ternary += 1,
+            ":" => {
+                let frame = self.frame();
+                frame.is_ternary_colon = frame.ternary > 0;
+                if frame.ternary > 0 {
+                    frame.ternary -= 1;
+                } else if frame.context == Context::Object {
+                    frame.in_value = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks whether a parenthesis starts the parameters of an arrow
+    /// function
+    fn is_arrow_params(&self, pos: usize) -> bool {
+        let mut depth = 0_usize;
+        for (end, token) in self.tokens.iter().enumerate().skip(pos) {
+            if let Token::Punctuator(punctuator) = token {
+                match punctuator.as_str() {
+                    "(" | "[" | "{" => depth += 1,
+                    ")" | "]" | "}" => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return self.is_at(self.next(end), "=>");
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        false
+    }
+
+    fn open_paren(&mut self, pos: usize) {
+        let mut frame = Frame::new(Context::Paren);
+        match self.pending {
+            Some(Pending::Params) => {
+                self.pending = None;
+                frame.pattern = Some(Kind::Let);
+                frame.after_close = AfterClose::Body;
+            }
+            Some(Pending::Catch) => {
+                self.pending = None;
+                let _ = self.push_scope(false);
+                frame.pattern = Some(Kind::Let);
+                frame.after_close = AfterClose::Body;
+            }
+            Some(Pending::For) => {
+                self.pending = None;
+                let _ = self.push_scope(false);
+                frame.after_close = AfterClose::ForBody;
+            }
+            _ => {
+                let current = self.frame().clone();
+                let is_method = matches!(current.context, Context::Object | Context::Class)
+                    && !current.in_value;
+                if is_method || self.is_arrow_params(pos) {
+                    let _ = self.push_scope(true);
+                    frame.pattern = Some(Kind::Let);
+                    if is_method {
+                        frame.after_close = AfterClose::Body;
+                    }
+                }
+            }
+        }
+        self.frames.push(frame);
+    }
+
+    /// Checks whether a brace opens a block instead of an object
+    fn is_block(&mut self, pos: usize) -> bool {
+        let prev = self.prev(pos);
+        if prev.is_none() || self.has_line_break_before(pos) {
+            return true;
+        }
+        if self.is_at(prev, ":") {
+            let frame = self.frame();
+            return frame.context == Context::Block && !frame.is_ternary_colon;
+        }
+        self.is_at_any(prev, &[";", "{", "}", ")", "else", "do", "try", "finally"])
+    }
+
+    fn open_brace(&mut self, pos: usize) {
+        let is_block = self.is_block(pos);
+        let frame = match self.pending {
+            Some(Pending::Body) => {
+                self.pending = None;
+                Frame {
+                    scopes: 1,
+                    ..Frame::new(Context::Block)
+                }
+            }
+            Some(Pending::Class(depth)) if depth == self.frames.len() => {
+                self.pending = None;
+                Frame::new(Context::Class)
+            }
+            _ if is_block => {
+                let _ = self.push_scope(false);
+                Frame {
+                    scopes: 1,
+                    ..Frame::new(Context::Block)
+                }
+            }
+            _ => Frame {
+                pattern: self.binding_kind(self.prev(pos)),
+                ..Frame::new(Context::Object)
+            },
+        };
+        self.frames.push(frame);
+    }
+
+    fn close(&mut self, pos: usize) {
+        if self.frames.len() == 1 {
+            return;
+        }
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+        self.pop_scopes(frame.scopes + frame.expression_scopes + frame.statement_scopes);
+        match frame.after_close {
+            AfterClose::None => {}
+            AfterClose::Body => self.pending = Some(Pending::Body),
+            AfterClose::ForBody => {
+                if self.is_at(self.next(pos), "{") {
+                    self.pending = Some(Pending::Body);
+                } else {
+                    self.frame().statement_scopes += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns the replacement of every renamed identifier by its position
+    fn rename(&self) -> HashMap<usize, String> {
+        let name_of = |occurrence: &Occurrence| match &self.tokens[occurrence.token] {
+            Token::Identifier(name) => name.as_str(),
+            _ => "",
+        };
+
+        let mut unresolved = HashSet::new();
+        let mut resolved = Vec::with_capacity(self.occurrences.len());
+        for occurrence in &self.occurrences {
+            let name = name_of(occurrence);
+            let mut scope = Some(occurrence.scope);
+            if !occurrence.is_declaration {
+                while let Some(id) = scope {
+                    if self.scopes[id]
+                        .bindings
+                        .iter()
+                        .any(|binding| binding == name)
+                    {
+                        break;
+                    }
+                    scope = self.scopes[id].parent;
+                }
+            }
+            match scope {
+                Some(scope) => resolved.push((occurrence, scope)),
+                None => {
+                    let _ = unresolved.insert(name);
+                }
+            }
+        }
+
+        let mut finals: HashMap<(usize, &str), String> = HashMap::new();
+        let mut in_function = Vec::with_capacity(self.scopes.len());
+        for (id, scope) in self.scopes.iter().enumerate() {
+            let is_in_function =
+                scope.is_function || scope.parent.is_some_and(|parent| in_function[parent]);
+            in_function.push(is_in_function);
+            if !is_in_function || scope.is_dynamic {
+                for binding in &scope.bindings {
+                    let _ = finals.insert((id, binding), binding.clone());
+                }
+                continue;
+            }
+            let mut avoid: HashSet<&str> = unresolved.clone();
+            let mut ancestor = scope.parent;
+            while let Some(parent) = ancestor {
+                for binding in &self.scopes[parent].bindings {
+                    if let Some(name) = finals.get(&(parent, binding.as_str())) {
+                        let _ = avoid.insert(name);
+                    }
+                }
+                ancestor = self.scopes[parent].parent;
+            }
+            let mut index = 0;
+            let mut names = Vec::with_capacity(scope.bindings.len());
+            for binding in &scope.bindings {
+                let name = loop {
+                    let name = generate_name(index);
+                    index += 1;
+                    if !avoid.contains(name.as_str()) && !RESERVED.contains(&name.as_str()) {
+                        break name;
+                    }
+                };
+                names.push((binding.as_str(), name));
+            }
+            for (binding, name) in names {
+                let _ = finals.insert((id, binding), name);
+            }
+        }
+
+        let mut names = HashMap::new();
+        for (occurrence, scope) in resolved {
+            let name = name_of(occurrence);
+            let Some(new_name) = finals.get(&(scope, name)) else {
+                continue;
+            };
+            if new_name != name {
+                let replacement = if occurrence.is_shorthand {
+                    format!("{name}:{new_name}")
+                } else {
+                    new_name.clone()
+                };
+                let _ = names.insert(occurrence.token, replacement);
+            }
+        }
+        names
+    }
+}
+
+/// Returns the name with the given index: `a`, `b`, ..., `Z`, `aa`, `ba`, ...
+fn generate_name(index: usize) -> String {
+    let mut name = String::new();
+    name.push(char::from(NAME_START[index % NAME_START.len()]));
+    let mut rest = index / NAME_START.len();
+    while rest > 0 {
+        rest -= 1;
+        name.push(char::from(NAME_PART[rest % NAME_PART.len()]));
+        rest /= NAME_PART.len();
+    }
+    name
+}
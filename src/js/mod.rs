@@ -0,0 +1,216 @@
+pub use crate::js::js_options::JsOptions;
+use crate::{
+    io::{
+        reader::{Filter, InternalReader},
+        stream::StreamFilter,
+    },
+    js::js_minifier::JsMinifier,
+};
+use std::io::Read;
+
+mod js_minifier;
+mod js_options;
+mod mangler;
+mod tokenizer;
+
+type JsFilter<I> = StreamFilter<I, JsMinifier>;
+
+/// Reader Implementation for JavaScript minification
+pub type Reader<R> = InternalReader<JsFilter<Filter<R>>>;
+
+/// Minifies a given String by JavaScript minification rules
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::js::minify;
+///
+/// fn main() {
+///     let js = r#"
+///         // comment
+///         function test ( a , b ) {
+///             return a + b ;
+///         }
+///     "#;
+///     let js_minified = minify(js);
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn minify(js: &str) -> String {
+    minify_with_options(js, JsOptions::default())
+}
+
+/// Minifies a given String by JavaScript minification rules, configured by
+/// the given options
+///
+/// Renaming local identifiers requires the complete script, so there is no
+/// [`Read`] based counterpart.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::js::{minify_with_options, JsOptions};
+///
+/// fn main() {
+///     let js = r#"
+///         function test ( first , second ) {
+///             var sum = first + second ;
+///             return sum ;
+///         }
+///     "#;
+///     let options = JsOptions::new().mangle_locals(true);
+///     let js_minified = minify_with_options(js, options);
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn minify_with_options(js: &str, options: JsOptions) -> String {
+    let filtered = js.chars();
+    let minified: String = JsFilter::new(filtered, JsMinifier::new()).collect();
+    if options.mangle_locals {
+        mangler::mangle(&minified)
+    } else {
+        minified
+    }
+}
+
+/// Minifies a given Read by JavaScript minification rules
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use std::fs::File;
+/// use std::io::Read;
+/// use minify::js::minify_from_read;
+///
+/// fn main() {
+///     let mut js_minified = String::new();
+///     let mut file = File::open("tests/files/test.js").expect("file not found");
+///     minify_from_read(file).read_to_string(&mut js_minified);
+/// }
+/// ```
+#[inline]
+pub fn minify_from_read<R: Read>(js: R) -> Reader<R> {
+    Reader::from_read(js, |chars| JsFilter::new(chars, JsMinifier::new()))
+}
+
+#[test]
+fn removal_from_read() {
+    use std::fs::File;
+
+    let file = File::open("tests/files/test.js").expect("file not found");
+    let expected = "function test(a,b){var c=a/b;return c\n+1;}\n\
+         var d=\"a  //  b\";var e=/ [/ ]+ /g.test(`x  ${d}  y`);";
+    let mut actual = String::new();
+    let _ = minify_from_read(file)
+        .read_to_string(&mut actual)
+        .expect("error at read");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn removal_of_comments() {
+    let input = "/* a */ var b = 1 ; // c\n/* d\n */ var e = b /* f */ / 2 ;";
+    let expected = "var b=1;var e=b/2;";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_strings_and_templates() {
+    let input = r#"a ( "b  /* c */" , 'd \' // e' , `f ${ g ( `h  ${ i }` ) }  // j` ) ;"#;
+    let expected = r#"a("b  /* c */",'d \' // e',`f ${g(`h  ${i}`)}  // j`);"#;
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_regular_expressions() {
+    let input =
+        "a = b / c / d ; e = f ( / [/]  \\/ / ) ; return /x  y/i . test ( g ) ; h = ( i ) / 2 ;";
+    let expected = "a=b/c/d;e=f(/ [/]  \\/ /);return/x  y/i.test(g);h=(i)/2;";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_division_after_postfix_operators() {
+    let input = "x = a++ / 2; y = \"/\"; z = b-- / 3";
+    let expected = "x=a++/2;y=\"/\";z=b--/3";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn keep_line_breaks_for_semicolon_insertion() {
+    let input = "a = b\n  ++ c\nreturn\n  d\nx = {\n  y : 1\n}\n[ 1 ] . map ( f )\n\ng ( )";
+    let expected = "a=b\n++c\nreturn\nd\nx={y:1}\n[1].map(f)\ng()";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn keep_separating_whitespaces() {
+    let input = "var a = b + + c - - d ; e = 1 .toString ( ) ; f = g / / h / ; i = j < ! -- k ;";
+    let expected = "var a=b+ +c- -d;e=1 .toString();f=g/ /h/;i=j< !--k;";
+    let actual = minify(input);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn mangle_parameters_and_locals() {
+    let input = "function test ( first , second ) { var sum = first + second ; let [ x , y ] = sum ; return x + y ; }";
+    let expected = "function test(a,b){var c=a+b;let[d,e]=c;return d+e;}";
+    let actual = minify_with_options(input, JsOptions::new().mangle_locals(true));
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn mangle_keeps_globals_and_properties() {
+    let input = "var total = 0 ; function add ( value ) { total += value . total ; return { value , total : a } ; }";
+    let expected = "var total=0;function add(b){total+=b.total;return{value:b,total:a};}";
+    let actual = minify_with_options(input, JsOptions::new().mangle_locals(true));
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn mangle_nested_functions() {
+    let input = "function outer ( first ) { const inner = ( second ) => first + second ; return function ( third ) { return inner ( third ) ; } ; }";
+    let expected = "function outer(a){const b=(c)=>a+c;return function(c){return b(c);};}";
+    let actual = minify_with_options(input, JsOptions::new().mangle_locals(true));
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn mangle_arrow_functions_continued_on_next_line() {
+    let input = "function f(items){ return items.reduce((sum, item) => sum\n  + item.price, 0) }";
+    let expected = "function f(a){return a.reduce((b,c)=>b\n+c.price,0)}";
+    let actual = minify_with_options(input, JsOptions::new().mangle_locals(true));
+    assert_eq!(actual, expected);
+
+    let input = "function g(arr){ return arr.map(x => x\n+ x) }\nx";
+    let expected = "function g(a){return a.map(b=>b\n+b)}\nx";
+    let actual = minify_with_options(input, JsOptions::new().mangle_locals(true));
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn mangle_after_division_by_postfix_operators() {
+    let input = "function f(count) { var total = count++ / 2 / count; return total }";
+    let expected = "function f(a){var b=a++/2/a;return b}";
+    let actual = minify_with_options(input, JsOptions::new().mangle_locals(true));
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn mangle_keeps_scopes_with_eval() {
+    let input = "function outer ( first ) { function inner ( second ) { return eval ( second ) ; } return inner ; } function other ( third ) { return third ; }";
+    let expected = "function outer(first){function inner(second){return eval(second);}return inner;}function other(a){return a;}";
+    let actual = minify_with_options(input, JsOptions::new().mangle_locals(true));
+    assert_eq!(actual, expected);
+}
@@ -0,0 +1,241 @@
+use crate::js::js_minifier::{is_identifier, is_line_terminator, REGEX_KEYWORDS};
+
+/// Punctuators consisting of more than one character, longest first
+const PUNCTUATORS: [&str; 33] = [
+    ">>>=", "...", "===", "!==", "**=", "<<=", ">>=", ">>>", "&&=", "||=", "??=", "=>", "==", "!=",
+    "<=", ">=", "&&", "||", "??", "?.", "++", "--", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=",
+    "**", "<<", ">>",
+];
+
+/// A javascript token, comments are expected to be removed already
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    /// Identifiers and keywords
+    Identifier(String),
+    Punctuator(String),
+    /// Strings, numbers, regular expressions, private names and the parts
+    /// of template literals outside of their substitutions
+    Literal(String),
+    Space,
+    LineBreak,
+}
+
+impl Token {
+    /// Checks whether the token is the given identifier or punctuator
+    pub fn is(&self, text: &str) -> bool {
+        match self {
+            Self::Identifier(token) | Self::Punctuator(token) => token == text,
+            _ => false,
+        }
+    }
+
+    pub fn write(&self, out: &mut String) {
+        match self {
+            Self::Identifier(token) | Self::Punctuator(token) | Self::Literal(token) => {
+                out.push_str(token);
+            }
+            Self::Space => out.push(' '),
+            Self::LineBreak => out.push('\n'),
+        }
+    }
+}
+
+/// Splits javascript into tokens
+pub fn tokenize(js: &str) -> Vec<Token> {
+    let chars: Vec<char> = js.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut brace_depth = 0_usize;
+    let mut template_depths = Vec::new();
+    while let Some(&item) = chars.get(pos) {
+        let start = pos;
+        let token = match item {
+            item if is_line_terminator(item) => {
+                pos += 1;
+                Token::LineBreak
+            }
+            item if item.is_whitespace() || item.eq(&'\u{feff}') => {
+                pos += 1;
+                Token::Space
+            }
+            '"' | '\'' => {
+                pos = skip_string(&chars, pos + 1, item);
+                literal(&chars, start, pos)
+            }
+            '`' => {
+                pos = skip_template(&chars, pos + 1, &mut template_depths, brace_depth);
+                literal(&chars, start, pos)
+            }
+            '}' if template_depths.last() == Some(&brace_depth) => {
+                let _ = template_depths.pop();
+                pos = skip_template(&chars, pos + 1, &mut template_depths, brace_depth);
+                literal(&chars, start, pos)
+            }
+            '#' if chars.get(pos + 1).copied().is_some_and(is_identifier) => {
+                pos = skip_word(&chars, pos + 1);
+                literal(&chars, start, pos)
+            }
+            item if item.is_ascii_digit()
+                || item.eq(&'.') && chars.get(pos + 1).is_some_and(char::is_ascii_digit) =>
+            {
+                pos = skip_number(&chars, pos);
+                literal(&chars, start, pos)
+            }
+            item if is_identifier(item) => {
+                pos = skip_word(&chars, pos);
+                Token::Identifier(chars[start..pos].iter().collect())
+            }
+            '/' if is_regex_allowed(&tokens) => {
+                pos = skip_regex(&chars, pos + 1);
+                literal(&chars, start, pos)
+            }
+            _ => {
+                let rest = &chars[pos..];
+                let punctuator = PUNCTUATORS
+                    .iter()
+                    .find(|punctuator| {
+                        punctuator.len() <= rest.len()
+                            && punctuator.chars().zip(rest).all(|(a, b)| a.eq(b))
+                            && !(**punctuator == "?."
+                                && rest.get(2).is_some_and(char::is_ascii_digit))
+                    })
+                    .map_or_else(|| item.to_string(), |punctuator| (*punctuator).to_owned());
+                pos += punctuator.chars().count();
+                match item {
+                    '{' => brace_depth += 1,
+                    '}' => brace_depth = brace_depth.saturating_sub(1),
+                    _ => {}
+                }
+                Token::Punctuator(punctuator)
+            }
+        };
+        tokens.push(token);
+    }
+    tokens
+}
+
+fn literal(chars: &[char], start: usize, end: usize) -> Token {
+    Token::Literal(chars[start..end].iter().collect())
+}
+
+/// Checks whether a slash starts a regular expression, using the same rules
+/// as the minifier
+fn is_regex_allowed(tokens: &[Token]) -> bool {
+    let last = tokens
+        .iter()
+        .rev()
+        .find(|token| !matches!(token, Token::Space | Token::LineBreak));
+    match last {
+        None | Some(Token::Space | Token::LineBreak) => true,
+        Some(Token::Identifier(word)) => REGEX_KEYWORDS.contains(&word.as_str()),
+        Some(Token::Literal(_)) => false,
+        // a slash after a postfix `++` or `--` is a division
+        Some(Token::Punctuator(punctuator)) => {
+            !matches!(punctuator.as_str(), ")" | "]" | "++" | "--")
+        }
+    }
+}
+
+fn skip_word(chars: &[char], mut pos: usize) -> usize {
+    while chars.get(pos).copied().is_some_and(is_identifier) {
+        if chars[pos].eq(&'\\') {
+            pos += 1;
+        }
+        pos += 1;
+    }
+    pos.min(chars.len())
+}
+
+fn skip_number(chars: &[char], start: usize) -> usize {
+    let is_hex = chars.get(start) == Some(&'0')
+        && chars
+            .get(start + 1)
+            .is_some_and(|item| item.eq_ignore_ascii_case(&'x'));
+    let mut pos = start;
+    while chars.get(pos).copied().is_some_and(|item| {
+        item.is_alphanumeric()
+            || matches!(item, '.' | '_')
+            || !is_hex && matches!(item, '+' | '-') && matches!(chars[pos - 1], 'e' | 'E')
+    }) {
+        pos += 1;
+    }
+    pos
+}
+
+fn skip_string(chars: &[char], mut pos: usize, quote: char) -> usize {
+    while let Some(&item) = chars.get(pos) {
+        pos += 1;
+        if item.eq(&'\\') {
+            pos += 1;
+        } else if item.eq(&quote) {
+            break;
+        }
+    }
+    pos.min(chars.len())
+}
+
+/// Skips a part of a template literal until its end or the start of a
+/// substitution
+fn skip_template(
+    chars: &[char],
+    mut pos: usize,
+    template_depths: &mut Vec<usize>,
+    brace_depth: usize,
+) -> usize {
+    while let Some(&item) = chars.get(pos) {
+        pos += 1;
+        match item {
+            '\\' => pos += 1,
+            '`' => break,
+            '$' if chars.get(pos) == Some(&'{') => {
+                template_depths.push(brace_depth);
+                pos += 1;
+                break;
+            }
+            _ => {}
+        }
+    }
+    pos.min(chars.len())
+}
+
+fn skip_regex(chars: &[char], mut pos: usize) -> usize {
+    let mut is_class = false;
+    while let Some(&item) = chars.get(pos) {
+        pos += 1;
+        match item {
+            '\\' => pos += 1,
+            '[' => is_class = true,
+            ']' => is_class = false,
+            '/' if !is_class => break,
+            _ => {}
+        }
+    }
+    skip_word(chars, pos.min(chars.len()))
+}
+
+#[test]
+fn division_after_postfix_operators() {
+    let tokens = tokenize("a++ / 2; b-- / c / d");
+    let punctuator = |text: &str| Token::Punctuator(text.to_owned());
+    let expected = vec![
+        Token::Identifier("a".to_owned()),
+        punctuator("++"),
+        Token::Space,
+        punctuator("/"),
+        Token::Space,
+        Token::Literal("2".to_owned()),
+        punctuator(";"),
+        Token::Space,
+        Token::Identifier("b".to_owned()),
+        punctuator("--"),
+        Token::Space,
+        punctuator("/"),
+        Token::Space,
+        Token::Identifier("c".to_owned()),
+        Token::Space,
+        punctuator("/"),
+        Token::Space,
+        Token::Identifier("d".to_owned()),
+    ];
+    assert_eq!(tokens, expected);
+}
@@ -1,7 +1,12 @@
+use crate::{
+    custom::{Minifier, Window},
+    io::multi_filter::Action,
+};
+
 #[derive(Debug, Clone, Copy)]
 pub struct JsonMinifier {
     pub is_string: bool,
-    pub escaped_quotation: u8,
+    pub escaped: bool,
 }
 
 impl Default for JsonMinifier {
@@ -14,43 +19,30 @@ impl JsonMinifier {
     pub const fn new() -> Self {
         Self {
             is_string: false,
-            escaped_quotation: 0,
+            escaped: false,
         }
     }
 }
 
-#[inline]
-pub fn keep_element(
-    minifier: &mut JsonMinifier,
-    item1: char,
-    item2: Option<char>,
-    _item3: Option<char>,
-    _item4: Option<char>,
-    _item5: Option<char>,
-    _item6: Option<char>,
-) -> bool {
-    let remove_element =
-        item1.is_ascii_control() || is_whitespace_outside_string(minifier, item1, item2);
-    !remove_element
+impl Minifier for JsonMinifier {
+    #[inline]
+    fn action(&mut self, window: &Window) -> Action {
+        let item = window.current();
+        let remove_element = item.is_ascii_control() || is_whitespace_outside_string(self, item);
+        (!remove_element).into()
+    }
 }
 
 #[inline]
-fn is_whitespace_outside_string(
-    minifier: &mut JsonMinifier,
-    item1: char,
-    item2: Option<char>,
-) -> bool {
-    if !minifier.is_string && item1.eq(&'"') {
-        minifier.is_string = true;
-    } else if minifier.is_string {
-        if item1.eq(&'\\') && item2.eq(&Some('"')) {
-            minifier.escaped_quotation = 4;
-        }
-        if minifier.escaped_quotation > 0 {
-            minifier.escaped_quotation -= 1;
-        } else if item1.eq(&'"') {
-            minifier.is_string = false;
-        }
+fn is_whitespace_outside_string(minifier: &mut JsonMinifier, item: char) -> bool {
+    if !minifier.is_string {
+        minifier.is_string = item.eq(&'"');
+    } else if minifier.escaped {
+        minifier.escaped = false;
+    } else if item.eq(&'\\') {
+        minifier.escaped = true;
+    } else if item.eq(&'"') {
+        minifier.is_string = false;
     }
-    !minifier.is_string && item1.is_whitespace()
+    !minifier.is_string && item.is_whitespace()
 }
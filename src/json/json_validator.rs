@@ -0,0 +1,511 @@
+use crate::io::reader::{Filter, Source};
+use std::{
+    error,
+    fmt::{self, Formatter},
+    io::{Error, ErrorKind, Read},
+    sync::Arc,
+};
+
+/// An enumeration of the ways in which json can be malformed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum JsonErrorKind {
+    /// A character which is not allowed at its position, like an unescaped
+    /// control character in a string or a closing bracket without a
+    /// matching opening bracket
+    UnexpectedCharacter(char),
+    /// A comma which is followed by the end of an array or object
+    TrailingComma,
+    /// The input ended before the json value was complete, like inside of a
+    /// string or before all arrays and objects were closed
+    UnexpectedEnd,
+}
+
+/// Error returned for input which is not valid json according to RFC 8259
+///
+/// The position refers to the character at which the input became invalid.
+/// Readers return the error as the inner error of an [`Error`] with the kind
+/// [`ErrorKind::InvalidData`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    kind: JsonErrorKind,
+    position: Position,
+}
+
+impl JsonError {
+    /// Returns the way in which the json is malformed
+    #[must_use]
+    pub const fn kind(&self) -> JsonErrorKind {
+        self.kind
+    }
+
+    /// Returns the line of the error, starting at `1`
+    #[must_use]
+    pub const fn line(&self) -> usize {
+        self.position.line
+    }
+
+    /// Returns the column of the error in characters, starting at `1`
+    #[must_use]
+    pub const fn column(&self) -> usize {
+        self.position.column
+    }
+
+    /// Returns the byte offset of the error, starting at `0`
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.position.offset
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            JsonErrorKind::UnexpectedCharacter(item) => write!(f, "unexpected character {item:?}")?,
+            JsonErrorKind::TrailingComma => write!(f, "trailing comma")?,
+            JsonErrorKind::UnexpectedEnd => write!(f, "unexpected end of input")?,
+        }
+        write!(
+            f,
+            " at line {}, column {} (byte {})",
+            self.position.line, self.position.column, self.position.offset
+        )
+    }
+}
+
+impl error::Error for JsonError {}
+
+impl From<JsonError> for Error {
+    fn from(error: JsonError) -> Self {
+        Self::new(ErrorKind::InvalidData, error)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Position {
+    line: usize,
+    column: usize,
+    offset: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Container {
+    Array,
+    Object,
+}
+
+/// Part of a number which was read last
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Number {
+    Minus,
+    Zero,
+    Integer,
+    Point,
+    Fraction,
+    Exponent,
+    ExponentSign,
+    ExponentDigits,
+}
+
+impl Number {
+    const fn is_complete(self) -> bool {
+        matches!(
+            self,
+            Self::Zero | Self::Integer | Self::Fraction | Self::ExponentDigits
+        )
+    }
+
+    /// Returns the part of the number after `item`, or `None` if `item` does
+    /// not belong to the number
+    const fn next(self, item: char) -> Option<Self> {
+        let digit = item.is_ascii_digit();
+        let exponent = matches!(item, 'e' | 'E');
+        match self {
+            Self::Minus if item == '0' => Some(Self::Zero),
+            Self::Minus | Self::Integer if digit => Some(Self::Integer),
+            Self::Zero | Self::Integer if item == '.' => Some(Self::Point),
+            Self::Point | Self::Fraction if digit => Some(Self::Fraction),
+            Self::Zero | Self::Integer | Self::Fraction if exponent => Some(Self::Exponent),
+            Self::Exponent if matches!(item, '+' | '-') => Some(Self::ExponentSign),
+            Self::Exponent | Self::ExponentSign | Self::ExponentDigits if digit => {
+                Some(Self::ExponentDigits)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Literal name of a json value
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Literal {
+    True,
+    False,
+    Null,
+}
+
+impl Literal {
+    const fn text(self) -> &'static str {
+        match self {
+            Self::True => "true",
+            Self::False => "false",
+            Self::Null => "null",
+        }
+    }
+}
+
+/// What the validator expects next
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum State {
+    /// A value
+    Value,
+    /// A value or the end of an empty array
+    ArrayStart,
+    /// A key or the end of an empty object
+    ObjectStart,
+    /// A key after a comma
+    Key,
+    /// The colon after a key
+    Colon,
+    /// A comma or the end of the enclosing array or object, or the end of
+    /// the input after the top level value
+    Next,
+    String {
+        key: bool,
+    },
+    Escape {
+        key: bool,
+    },
+    /// Hexadecimal digits of an unicode escape, with the number of missing
+    /// digits
+    Unicode {
+        key: bool,
+        missing: u8,
+    },
+    Number(Number),
+    /// Literal with the number of characters which are read already
+    Literal {
+        literal: Literal,
+        matched: u8,
+    },
+}
+
+/// Validates json according to RFC 8259, one character at a time
+#[derive(Debug, Clone)]
+pub struct JsonValidator {
+    state: State,
+    containers: Vec<Container>,
+    /// Position of the next character
+    position: Position,
+    /// Position of the last comma
+    comma: Position,
+}
+
+impl Default for JsonValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonValidator {
+    pub const fn new() -> Self {
+        let position = Position {
+            line: 1,
+            column: 1,
+            offset: 0,
+        };
+        Self {
+            state: State::Value,
+            containers: Vec::new(),
+            position,
+            comma: position,
+        }
+    }
+
+    /// Validates the next character of the input
+    pub fn push(&mut self, item: char) -> Result<(), JsonError> {
+        self.state = self.next_state(item)?;
+        if item == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+        self.position.offset += item.len_utf8();
+        Ok(())
+    }
+
+    /// Validates that the input ended after a complete json value
+    pub fn finish(&mut self) -> Result<(), JsonError> {
+        if let State::Number(number) = self.state {
+            if number.is_complete() {
+                self.state = State::Next;
+            }
+        }
+        if self.state == State::Next && self.containers.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(JsonErrorKind::UnexpectedEnd))
+        }
+    }
+
+    fn next_state(&mut self, item: char) -> Result<State, JsonError> {
+        let is_whitespace = matches!(item, ' ' | '\t' | '\n' | '\r');
+        let state = match self.state {
+            State::Value | State::ArrayStart | State::ObjectStart | State::Key | State::Colon
+                if is_whitespace =>
+            {
+                self.state
+            }
+            State::Next if is_whitespace => State::Next,
+            State::Value if item == ']' && self.containers.last() == Some(&Container::Array) => {
+                return Err(self.trailing_comma());
+            }
+            State::ArrayStart if item == ']' => self.close(Container::Array, item)?,
+            State::Value | State::ArrayStart => self.start_value(item)?,
+            State::ObjectStart | State::Key if item == '"' => State::String { key: true },
+            State::ObjectStart if item == '}' => self.close(Container::Object, item)?,
+            State::Key if item == '}' => return Err(self.trailing_comma()),
+            State::Colon if item == ':' => State::Value,
+            State::Next => match (item, self.containers.last()) {
+                (',', Some(Container::Array)) => {
+                    self.comma = self.position;
+                    State::Value
+                }
+                (',', Some(Container::Object)) => {
+                    self.comma = self.position;
+                    State::Key
+                }
+                (']', _) => self.close(Container::Array, item)?,
+                ('}', _) => self.close(Container::Object, item)?,
+                _ => return Err(self.unexpected(item)),
+            },
+            State::String { key } => match item {
+                '"' if key => State::Colon,
+                '"' => State::Next,
+                '\\' => State::Escape { key },
+                '\u{0}'..='\u{1f}' => return Err(self.unexpected(item)),
+                _ => self.state,
+            },
+            State::Escape { key } => match item {
+                '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' => State::String { key },
+                'u' => State::Unicode { key, missing: 4 },
+                _ => return Err(self.unexpected(item)),
+            },
+            State::Unicode { key, missing } if item.is_ascii_hexdigit() => match missing {
+                1 => State::String { key },
+                _ => State::Unicode {
+                    key,
+                    missing: missing - 1,
+                },
+            },
+            State::Number(number) => match number.next(item) {
+                Some(number) => State::Number(number),
+                // the number ends before `item`, which follows the value
+                None if number.is_complete() => {
+                    self.state = State::Next;
+                    self.next_state(item)?
+                }
+                None => return Err(self.unexpected(item)),
+            },
+            State::Literal { literal, matched } => {
+                let rest = &literal.text()[usize::from(matched)..];
+                match rest.strip_prefix(item) {
+                    Some("") => State::Next,
+                    Some(_) => State::Literal {
+                        literal,
+                        matched: matched + 1,
+                    },
+                    None => return Err(self.unexpected(item)),
+                }
+            }
+            State::ObjectStart | State::Key | State::Colon | State::Unicode { .. } => {
+                return Err(self.unexpected(item));
+            }
+        };
+        Ok(state)
+    }
+
+    fn start_value(&mut self, item: char) -> Result<State, JsonError> {
+        let state = match item {
+            '[' => {
+                self.containers.push(Container::Array);
+                State::ArrayStart
+            }
+            '{' => {
+                self.containers.push(Container::Object);
+                State::ObjectStart
+            }
+            '"' => State::String { key: false },
+            '-' => State::Number(Number::Minus),
+            '0' => State::Number(Number::Zero),
+            '1'..='9' => State::Number(Number::Integer),
+            't' => State::Literal {
+                literal: Literal::True,
+                matched: 1,
+            },
+            'f' => State::Literal {
+                literal: Literal::False,
+                matched: 1,
+            },
+            'n' => State::Literal {
+                literal: Literal::Null,
+                matched: 1,
+            },
+            _ => return Err(self.unexpected(item)),
+        };
+        Ok(state)
+    }
+
+    fn close(&mut self, container: Container, item: char) -> Result<State, JsonError> {
+        if self.containers.last() != Some(&container) {
+            return Err(self.unexpected(item));
+        }
+        let _ = self.containers.pop();
+        Ok(State::Next)
+    }
+
+    const fn error(&self, kind: JsonErrorKind) -> JsonError {
+        JsonError {
+            kind,
+            position: self.position,
+        }
+    }
+
+    const fn unexpected(&self, item: char) -> JsonError {
+        self.error(JsonErrorKind::UnexpectedCharacter(item))
+    }
+
+    const fn trailing_comma(&self) -> JsonError {
+        JsonError {
+            kind: JsonErrorKind::TrailingComma,
+            position: self.comma,
+        }
+    }
+}
+
+/// Iterator over the characters of a [`Filter`], which validates them if a
+/// validator is given and ends at the first error, leaving it in the
+/// [`Source`] of the reader
+#[derive(Debug)]
+pub struct Validate<R> {
+    chars: Filter<R>,
+    validator: Option<JsonValidator>,
+    source: Arc<Source>,
+    failed: bool,
+}
+
+impl<R> Validate<R> {
+    pub fn new(chars: Filter<R>, strict: bool) -> Self {
+        let source = chars.source();
+        Self {
+            chars,
+            validator: strict.then(JsonValidator::new),
+            source,
+            failed: false,
+        }
+    }
+}
+
+impl<R: Read> Iterator for Validate<R> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        if self.failed {
+            return None;
+        }
+        let item = self.chars.next();
+        let Some(validator) = self.validator.as_mut() else {
+            return item;
+        };
+        let result = match item {
+            Some(item) => validator.push(item),
+            None => validator.finish(),
+        };
+        if let Err(error) = result {
+            self.failed = true;
+            self.source.fail(Error::from(error));
+            return None;
+        }
+        item
+    }
+}
+
+#[cfg(test)]
+fn validate(json: &str) -> Result<(), JsonError> {
+    let mut validator = JsonValidator::new();
+    for item in json.chars() {
+        validator.push(item)?;
+    }
+    validator.finish()
+}
+
+#[test]
+fn accept_valid_json() {
+    let valid = [
+        "0",
+        "-0.5e+10",
+        "12E3",
+        "\"\"",
+        " true ",
+        "null",
+        "[]",
+        "{}",
+        "[1,[false,{}],\"a\\\"\\\\\\/\\b\\f\\n\\r\\t\\u00e9b\"]",
+        "{\"a\":{\"b\":[null]},\"c\":\"ü\"}\r\n",
+    ];
+    for json in valid {
+        assert_eq!(validate(json), Ok(()), "{json}");
+    }
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn reject_invalid_json() {
+    let invalid = [
+        ("", JsonErrorKind::UnexpectedEnd),
+        ("01", JsonErrorKind::UnexpectedCharacter('1')),
+        ("1.", JsonErrorKind::UnexpectedEnd),
+        ("-a", JsonErrorKind::UnexpectedCharacter('a')),
+        ("tru", JsonErrorKind::UnexpectedEnd),
+        ("nul1", JsonErrorKind::UnexpectedCharacter('1')),
+        ("\"a\tb\"", JsonErrorKind::UnexpectedCharacter('\t')),
+        ("\"\\x\"", JsonErrorKind::UnexpectedCharacter('x')),
+        ("\"\\u12g4\"", JsonErrorKind::UnexpectedCharacter('g')),
+        ("[1 2]", JsonErrorKind::UnexpectedCharacter('2')),
+        ("[1,]", JsonErrorKind::TrailingComma),
+        ("{\"a\":1,}", JsonErrorKind::TrailingComma),
+        ("{\"a\" 1}", JsonErrorKind::UnexpectedCharacter('1')),
+        ("{1:2}", JsonErrorKind::UnexpectedCharacter('1')),
+        ("[}", JsonErrorKind::UnexpectedCharacter('}')),
+        ("[[]", JsonErrorKind::UnexpectedEnd),
+        ("[]]", JsonErrorKind::UnexpectedCharacter(']')),
+        ("1 2", JsonErrorKind::UnexpectedCharacter('2')),
+        ("'a'", JsonErrorKind::UnexpectedCharacter('\'')),
+        ("\u{a0}1", JsonErrorKind::UnexpectedCharacter('\u{a0}')),
+    ];
+    for (json, kind) in invalid {
+        let error = validate(json).expect_err(json);
+        assert_eq!(error.kind(), kind, "{json}");
+    }
+}
+
+#[test]
+fn position_of_error() {
+    let error = validate("{\n  \"ä\": [1,\n  ]\n}").expect_err("trailing comma is accepted");
+    assert_eq!(error.kind(), JsonErrorKind::TrailingComma);
+    assert_eq!((error.line(), error.column(), error.offset()), (2, 10, 12));
+    assert_eq!(
+        error.to_string(),
+        "trailing comma at line 2, column 10 (byte 12)"
+    );
+
+    let error = validate("[\"a\n\"]").expect_err("newline in string is accepted");
+    assert_eq!((error.line(), error.column(), error.offset()), (1, 4, 3));
+    assert_eq!(
+        error.to_string(),
+        "unexpected character '\\n' at line 1, column 4 (byte 3)"
+    );
+
+    let error = validate("{\"a\": [").expect_err("unbalanced brackets are accepted");
+    assert_eq!((error.line(), error.column(), error.offset()), (1, 8, 7));
+}
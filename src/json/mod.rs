@@ -1,24 +1,49 @@
+#[cfg(any(feature = "tokio", feature = "futures"))]
+use crate::io::async_io::{InternalAsyncReader, InternalAsyncWriter};
+pub use crate::json::json_validator::{JsonError, JsonErrorKind};
 use crate::{
-    io::{multi_filter::MultiFilter, reader::InternalReader},
-    json::json_minifier::{keep_element, JsonMinifier},
+    custom::{action, ActionMethod, LOOKAHEAD},
+    io::{
+        minifier::InternalMinifier,
+        multi_filter::{MultiFilter, MultiStream},
+        reader::InternalReader,
+        writer::InternalWriter,
+    },
+    json::{
+        json_minifier::JsonMinifier,
+        json_validator::{JsonValidator, Validate},
+    },
+};
+use std::{
+    io::{Read, Write},
+    iter::Iterator,
 };
-use std::{io::Read, iter::Iterator, str::Chars};
 
 mod json_minifier;
+mod json_validator;
 
-type JsonMethod = fn(
-    &mut JsonMinifier,
-    char,
-    Option<char>,
-    Option<char>,
-    Option<char>,
-    Option<char>,
-    Option<char>,
-) -> bool;
-type JsonFilter<'a> = MultiFilter<Chars<'a>, JsonMethod, JsonMinifier>;
+type JsonMethod = ActionMethod<JsonMinifier>;
+type JsonFilter<I> = MultiFilter<I, JsonMethod, JsonMinifier, LOOKAHEAD>;
+type JsonStream = MultiStream<JsonMethod, JsonMinifier, LOOKAHEAD>;
 
 /// Reader Implementation for JSON minification
-pub type Reader<R> = InternalReader<R, JsonMethod, JsonMinifier>;
+pub type Reader<R> = InternalReader<JsonFilter<Validate<R>>>;
+
+/// Writer Implementation for JSON minification
+pub type Writer<W> = InternalWriter<W, JsonStream>;
+
+/// Incremental Minifier Implementation for JSON minification
+pub type Minifier = InternalMinifier<JsonStream>;
+
+/// Async Reader Implementation for JSON minification, implementing the
+/// `AsyncRead` trait of the enabled `tokio` or `futures` feature
+#[cfg(any(feature = "tokio", feature = "futures"))]
+pub type AsyncReader<R> = InternalAsyncReader<R, JsonStream>;
+
+/// Async Writer Implementation for JSON minification, implementing the
+/// `AsyncWrite` trait of the enabled `tokio` or `futures` feature
+#[cfg(any(feature = "tokio", feature = "futures"))]
+pub type AsyncWriter<W> = InternalAsyncWriter<W, JsonStream>;
 
 /// Minifies a given String by JSON minification rules
 ///
@@ -42,7 +67,7 @@ pub type Reader<R> = InternalReader<R, JsonMethod, JsonMinifier>;
 #[must_use]
 pub fn minify(json: &str) -> String {
     let filtered = json.chars();
-    JsonFilter::new(filtered, keep_element).collect()
+    JsonFilter::new(filtered, action::<JsonMinifier, LOOKAHEAD>).collect()
 }
 
 /// Minifies a given Read by JSON minification rules
@@ -63,7 +88,196 @@ pub fn minify(json: &str) -> String {
 /// ```
 #[inline]
 pub fn minify_from_read<R: Read>(json: R) -> Reader<R> {
-    Reader::new(json, keep_element)
+    Reader::from_read(json, |chars| {
+        JsonFilter::new(
+            Validate::new(chars, false),
+            action::<JsonMinifier, LOOKAHEAD>,
+        )
+    })
+}
+
+/// Minifies a given String by JSON minification rules, after validating it
+/// according to RFC 8259
+///
+/// # Errors
+///
+/// Returns a [`JsonError`] with the position of the first character which
+/// makes the input invalid json.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::json::minify_strict;
+///
+/// fn main() {
+///     let json = r#"{ "test": [1, 2,] }"#;
+///     let error = minify_strict(json).expect_err("trailing comma is accepted");
+///     assert_eq!(
+///         error.to_string(),
+///         "trailing comma at line 1, column 16 (byte 15)"
+///     );
+/// }
+/// ```
+#[inline]
+pub fn minify_strict(json: &str) -> Result<String, JsonError> {
+    let mut validator = JsonValidator::new();
+    let mut error = None;
+    // the input ends at the first error
+    let filtered = json
+        .chars()
+        .map_while(|item| match validator.push(item) {
+            Ok(()) => Some(item),
+            Err(err) => {
+                error = Some(err);
+                None
+            }
+        })
+        .fuse();
+    let minified = JsonFilter::new(filtered, action::<JsonMinifier, LOOKAHEAD>).collect();
+    if let Some(error) = error {
+        return Err(error);
+    }
+    validator.finish()?;
+    Ok(minified)
+}
+
+/// Minifies a given Read by JSON minification rules, while validating it
+/// according to RFC 8259
+///
+/// Reading returns an error of the kind `InvalidData` with a [`JsonError`]
+/// as inner error at the first character which makes the input invalid
+/// json. The output before the error is returned by the previous reads.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use std::io::{ErrorKind, Read};
+/// use minify::json::{minify_from_read_strict, JsonError};
+///
+/// fn main() {
+///     let input: &[u8] = b"{ \"test\": \"test }";
+///     let mut json_minified = String::new();
+///     let error = minify_from_read_strict(input)
+///         .read_to_string(&mut json_minified)
+///         .expect_err("unterminated string is accepted");
+///     assert_eq!(error.kind(), ErrorKind::InvalidData);
+///     let error = error.get_ref().and_then(|error| error.downcast_ref::<JsonError>());
+///     assert_eq!(error.map(JsonError::offset), Some(17));
+/// }
+/// ```
+#[inline]
+pub fn minify_from_read_strict<R: Read>(json: R) -> Reader<R> {
+    Reader::from_read(json, |chars| {
+        JsonFilter::new(
+            Validate::new(chars, true),
+            action::<JsonMinifier, LOOKAHEAD>,
+        )
+    })
+}
+
+/// Minifies everything written to the returned Writer by JSON
+/// minification rules and writes the result into the given Write
+///
+/// The last characters are held back as lookahead until more input is
+/// written or the Writer is finished by `Writer::finish` or dropped.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use std::io::Write;
+/// use minify::json::minify_to_write;
+///
+/// fn main() {
+///     let mut writer = minify_to_write(Vec::new());
+///     writer.write_all(br#"{ "test" : 1 }"#).expect("error at write");
+///     let json_minified = writer.finish().expect("error at write");
+/// }
+/// ```
+#[inline]
+pub fn minify_to_write<W: Write>(json: W) -> Writer<W> {
+    Writer::new(
+        json,
+        JsonStream::with_minifier(action::<JsonMinifier, LOOKAHEAD>, JsonMinifier::default()),
+    )
+}
+
+/// Creates a Minifier, which minifies input fed in chunks by JSON
+/// minification rules
+///
+/// The last characters of every chunk are held back as lookahead until the
+/// next chunk is fed or the Minifier is finished.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::json::minifier;
+///
+/// fn main() {
+///     let mut minifier = minifier();
+///     let mut json_minified: String = minifier.feed(r#"{ "test" "#).collect();
+///     json_minified.extend(minifier.feed(r#": 1 }"#));
+///     json_minified.push_str(&minifier.finish().expect("incomplete input"));
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn minifier() -> Minifier {
+    Minifier::new(JsonStream::with_minifier(
+        action::<JsonMinifier, LOOKAHEAD>,
+        JsonMinifier::default(),
+    ))
+}
+
+/// Minifies a given async reader by JSON minification rules
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::json::minify_from_async_read;
+///
+/// fn main() {
+///     let json: &[u8] = br#"{ "test" : 1 }"#;
+///     // read the minified json like any other `AsyncRead`
+///     let reader = minify_from_async_read(json);
+/// }
+/// ```
+#[cfg(any(feature = "tokio", feature = "futures"))]
+#[inline]
+pub fn minify_from_async_read<R>(json: R) -> AsyncReader<R> {
+    AsyncReader::new(
+        json,
+        JsonStream::with_minifier(action::<JsonMinifier, LOOKAHEAD>, JsonMinifier::default()),
+    )
+}
+
+/// Minifies everything written to the returned async writer by JSON
+/// minification rules and writes the result into the given async writer
+///
+/// The remaining output is written when the writer is shut down or closed.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate minify;
+/// use minify::json::minify_to_async_write;
+///
+/// fn main() {
+///     // write the json to minify like to any other `AsyncWrite`
+///     let writer = minify_to_async_write(Vec::<u8>::new());
+/// }
+/// ```
+#[cfg(any(feature = "tokio", feature = "futures"))]
+#[inline]
+pub fn minify_to_async_write<W>(json: W) -> AsyncWriter<W> {
+    AsyncWriter::new(
+        json,
+        JsonStream::with_minifier(action::<JsonMinifier, LOOKAHEAD>, JsonMinifier::default()),
+    )
 }
 
 #[test]
@@ -100,3 +314,300 @@ fn removal_of_whitespace_outside_of_tags() {
     let actual = minify(input);
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn keep_whitespace_in_string_after_escaped_backslash() {
+    let input = r#"["\\", "a b", "\\\" c"]"#;
+    let expected = r#"["\\","a b","\\\" c"]"#;
+    assert_eq!(minify(input), expected);
+    let actual = minify_strict(input).expect("valid json is rejected");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn removal_with_validation() {
+    let input = r#"
+            {
+              "test": "\" test2",
+              "test2": "",
+              "test3": [1, -2.5e3, true, null]
+            }
+        "#;
+    let expected = "{\"test\":\"\\\" test2\",\"test2\":\"\",\"test3\":[1,-2.5e3,true,null]}";
+    let actual = minify_strict(input).expect("valid json is rejected");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn return_position_of_invalid_json() {
+    let input = "{\n  \"test\": [1, 2\n}";
+    let error = minify_strict(input).expect_err("unbalanced brackets are accepted");
+    assert_eq!(error.kind(), JsonErrorKind::UnexpectedCharacter('}'));
+    assert_eq!((error.line(), error.column(), error.offset()), (3, 1, 18));
+
+    let error = minify_strict("{\"a\":[2,]}").expect_err("trailing comma is accepted");
+    assert_eq!(error.kind(), JsonErrorKind::TrailingComma);
+    assert_eq!(error.offset(), 7);
+}
+
+#[test]
+fn return_error_for_invalid_json_from_read() {
+    use std::io::ErrorKind;
+
+    let mut reader = minify_from_read_strict(&b"[\"a\", \"b\",]"[..]);
+    let mut actual = String::new();
+    let error = reader
+        .read_to_string(&mut actual)
+        .expect_err("trailing comma is accepted");
+    assert_eq!(actual, "[\"a\",\"b\",");
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        error.to_string(),
+        "trailing comma at line 1, column 10 (byte 9)"
+    );
+}
+
+#[test]
+fn return_error_for_invalid_utf8_before_end_from_read() {
+    use std::io::ErrorKind;
+
+    let mut reader = minify_from_read_strict(&b"[\"a\xff"[..]);
+    let mut actual = String::new();
+    let error = reader
+        .read_to_string(&mut actual)
+        .expect_err("invalid utf8 is accepted");
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        error.to_string(),
+        "byte stream did not contain valid utf8 at byte 3"
+    );
+}
+
+#[test]
+fn return_no_output_after_invalid_utf8_from_read() {
+    use std::io::ErrorKind;
+
+    let mut reader = minify_from_read(&b"[ 1 , \xff 2 , 3 ]"[..]);
+    let mut actual = Vec::new();
+    let error = reader
+        .read_to_end(&mut actual)
+        .expect_err("invalid utf8 is accepted");
+    assert_eq!(actual, b"[1,");
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        error.to_string(),
+        "byte stream did not contain valid utf8 at byte 6"
+    );
+}
+
+#[test]
+fn return_error_of_reader() {
+    use std::io::{self, Error, ErrorKind};
+
+    struct FailingReader<'a>(&'a [u8]);
+
+    impl Read for FailingReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(Error::new(ErrorKind::ConnectionReset, "reset"));
+            }
+            self.0.read(buf)
+        }
+    }
+
+    let mut reader = minify_from_read(FailingReader(b"{ \"a\" : 1 "));
+    let mut buf = [0; 16];
+    let read = reader.read(&mut buf).expect("error at read");
+    assert_eq!(&buf[..read], b"{\"a\":1");
+    let error = reader
+        .read(&mut buf)
+        .expect_err("error of reader is dropped");
+    assert_eq!(error.kind(), ErrorKind::ConnectionReset);
+    assert_eq!(error.to_string(), "reset at byte 10");
+}
+
+#[test]
+fn read_multibyte_characters_in_small_chunks() {
+    use std::io;
+
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl Read for ByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.0.len()).min(1);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    let input = "{ \"ä\" : \"€  𝄞\" }";
+    let expected = "{\"ä\":\"€  𝄞\"}";
+    let mut reader = minify_from_read(ByteReader(input.as_bytes()));
+    let mut actual = Vec::new();
+    let mut buf = [0; 1];
+    while reader.read(&mut buf).expect("error at read") > 0 {
+        actual.push(buf[0]);
+    }
+    assert_eq!(String::from_utf8(actual).expect("invalid utf8"), expected);
+}
+
+#[test]
+fn removal_to_write() {
+    let input = "{\n  \"a\" : \"b \\\" c\" ,\n  \"d\" : [ 1 , 2 ]\n}\n";
+    let mut writer = minify_to_write(Vec::new());
+    for chunk in input.as_bytes().chunks(4) {
+        writer.write_all(chunk).expect("error at write");
+    }
+    writer.flush().expect("error at flush");
+    let actual = writer.finish().expect("error at write");
+    assert_eq!(
+        String::from_utf8(actual).expect("invalid utf8"),
+        minify(input)
+    );
+}
+
+#[test]
+fn removal_with_minifier() {
+    let input = "{\n  \"a\" : \"b \\\" c\" ,\n  \"d\" : [ 1 , 2 ]\n}\n";
+    let mut minifier = minifier();
+    let mut actual = String::new();
+    for (pos, _) in input.char_indices().step_by(3) {
+        let end = (pos + 3).min(input.len());
+        actual.extend(minifier.feed(&input[pos..end]));
+    }
+    actual.push_str(&minifier.finish().expect("error at finish"));
+    assert_eq!(actual, minify(input));
+}
+
+#[test]
+fn return_error_for_incomplete_character_with_minifier() {
+    let mut minifier = minifier();
+    let mut actual = Vec::new();
+    minifier
+        .feed_bytes(b"[ \"\xe2\x82", &mut actual)
+        .expect("error at feed");
+    let error = minifier
+        .finish()
+        .expect_err("incomplete character is ignored");
+    assert_eq!(
+        error.to_string(),
+        "byte stream did not contain valid utf8 at byte 3"
+    );
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn removal_from_futures_read_and_to_futures_write() {
+    use futures_io::{AsyncRead, AsyncWrite};
+    use std::{
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+
+    let input = "{\n  \"a\" : \"b \\\" c\" ,\n  \"d\" : [ 1 , 2 ]\n}\n";
+    let mut cx = Context::from_waker(Waker::noop());
+
+    let mut reader = minify_from_async_read(input.as_bytes());
+    let mut actual = Vec::new();
+    let mut buf = [0; 5];
+    loop {
+        let poll = Pin::new(&mut reader).poll_read(&mut cx, &mut buf);
+        let Poll::Ready(Ok(read)) = poll else {
+            panic!("error at read");
+        };
+        if read == 0 {
+            break;
+        }
+        actual.extend_from_slice(&buf[..read]);
+    }
+    assert_eq!(
+        String::from_utf8(actual).expect("invalid utf8"),
+        minify(input)
+    );
+
+    let mut writer = minify_to_async_write(Vec::new());
+    for chunk in input.as_bytes().chunks(3) {
+        let poll = Pin::new(&mut writer).poll_write(&mut cx, chunk);
+        assert!(matches!(poll, Poll::Ready(Ok(len)) if len == chunk.len()));
+    }
+    let poll = Pin::new(&mut writer).poll_close(&mut cx);
+    assert!(matches!(poll, Poll::Ready(Ok(()))));
+    let actual = writer.into_inner();
+    assert_eq!(
+        String::from_utf8(actual).expect("invalid utf8"),
+        minify(input)
+    );
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn return_output_before_error_from_futures_read() {
+    use futures_io::AsyncRead;
+    use std::{
+        io::ErrorKind,
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut reader = minify_from_async_read(&b"[ 1 , \xff 2 , 3 ]"[..]);
+    let mut buf = [0; 16];
+    let poll = Pin::new(&mut reader).poll_read(&mut cx, &mut buf);
+    let Poll::Ready(Ok(read)) = poll else {
+        panic!("output before the error is dropped");
+    };
+    assert_eq!(&buf[..read], b"[1,");
+    let poll = Pin::new(&mut reader).poll_read(&mut cx, &mut buf);
+    let Poll::Ready(Err(error)) = poll else {
+        panic!("invalid utf8 is accepted");
+    };
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    let poll = Pin::new(&mut reader).poll_read(&mut cx, &mut buf);
+    assert!(matches!(poll, Poll::Ready(Ok(0))));
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn replace_invalid_utf8_from_futures_read_with_lossy() {
+    use futures_io::AsyncRead;
+    use std::{
+        io::{self, Read},
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+
+    /// Returns one byte per read
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl AsyncRead for ByteReader<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            let len = buf.len().min(1);
+            Poll::Ready(self.0.read(&mut buf[..len]))
+        }
+    }
+
+    let input = b"[ \"\xe2\x82\xac \xe2\x82 a \xff\" ]";
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut reader = minify_from_async_read(ByteReader(input)).lossy(true);
+    let mut actual = Vec::new();
+    let mut buf = [0; 5];
+    loop {
+        let poll = Pin::new(&mut reader).poll_read(&mut cx, &mut buf);
+        let Poll::Ready(Ok(read)) = poll else {
+            panic!("error at read");
+        };
+        if read == 0 {
+            break;
+        }
+        actual.extend_from_slice(&buf[..read]);
+    }
+    assert_eq!(
+        String::from_utf8(actual).expect("invalid utf8"),
+        "[\"\u{20ac} \u{fffd} a \u{fffd}\"]"
+    );
+}
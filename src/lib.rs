@@ -1,4 +1,4 @@
-//! Minification tool for html and json
+//! Minification tool for html, css, javascript and json
 //!
 //! # Usage
 //!
@@ -15,6 +15,18 @@
 //! extern crate minify;
 //! ```
 //!
+//! Async readers and writers for html and json, implementing `AsyncRead` and
+//! `AsyncWrite` of tokio or futures, are enabled by the `tokio` and `futures`
+//! features:
+//!
+//! ```toml
+//! [dependencies]
+//! minify = { version = "1.4", features = ["tokio"] }
+//! ```
+//!
+//! Like the blocking readers, async readers return the error of invalid utf8
+//! after the output before it, or replace it by `U+FFFD` with `lossy(true)`.
+//!
 //! # Minify Html
 //!
 //! The following rules are applied for html minification:
@@ -24,6 +36,11 @@
 //! * Removal of multiple whitespaces
 //! * Removal of whitespaces before and after greater-than and less-than signs
 //!   * `_<_html_>_` => `<html>`
+//! * Removal of whitespaces between attributes, attribute values are kept as is
+//! * Content of `pre` and `textarea` elements is kept as is
+//! * Content of `style` elements and attributes is minified by css rules
+//! * Content of `script` elements is minified by javascript or json rules
+//!   depending on its `type`, scripts of unknown types are kept as is
 //!
 //! ```rust
 //! extern crate minify;
@@ -42,6 +59,68 @@
 //! }
 //! ```
 //!
+//! Comment and whitespace handling can be configured with `HtmlOptions` and
+//! [`html::minify_with_options`].
+//!
+//! # Minify CSS
+//!
+//! The following rules are applied for css minification:
+//!
+//! * Removal of comments
+//! * Removal of multiple whitespaces
+//! * Removal of whitespaces around braces, semicolons, colons and commas
+//!   * `a { color : red ; }` => `a{color:red}`
+//! * Removal of the last semicolon in a block
+//! * Strings and `url(...)` values are kept as is
+//!
+//! ```rust
+//! extern crate minify;
+//! use minify::css::minify;
+//!
+//! fn main() {
+//!     let css = r#"
+//!         a:hover {
+//!             color : red ;
+//!         }
+//!     "#;
+//!     let css_minified = minify(css);
+//! }
+//! ```
+//!
+//! Shortening of colors, numbers, units and font weights can be enabled with
+//! `CssOptions` and [`css::minify_with_options`].
+//!
+//! Merging of rules with identical selectors, removal of overridden declarations
+//! and folding of `margin` and `padding` longhands is done by
+//! [`css::minify_advanced`].
+//!
+//! # Minify JavaScript
+//!
+//! The following rules are applied for javascript minification:
+//!
+//! * Removal of comments
+//! * Removal of whitespaces which do not separate tokens
+//! * Line breaks are kept where automatic semicolon insertion depends on them
+//! * Strings, template literals and regular expressions are kept as is
+//!
+//! ```rust
+//! extern crate minify;
+//! use minify::js::minify;
+//!
+//! fn main() {
+//!     let js = r#"
+//!         // comment
+//!         function test ( a , b ) {
+//!             return a + b ;
+//!         }
+//!     "#;
+//!     let js_minified = minify(js);
+//! }
+//! ```
+//!
+//! Renaming of local variables, parameters and functions to short names can be
+//! enabled with `JsOptions` and [`js::minify_with_options`].
+//!
 //! # Minify JSON
 //!
 //! The following rules are applied for json minification:
@@ -63,16 +142,55 @@
 //!     let json_minified = minify(json);
 //! }
 //! ```
+//!
+//! Malformed json like unterminated strings, trailing commas or unbalanced
+//! brackets is rejected with its line, column and byte offset by
+//! [`json::minify_strict`] and [`json::minify_from_read_strict`].
+//!
+//! # Minify Custom Formats
+//!
+//! Custom formats can be minified by implementing the [`custom::Minifier`]
+//! trait, which decides for every character whether it is kept, dropped or
+//! replaced, given the characters following it. Minifiers which only drop
+//! characters implement `keep`, others implement `action`. The number of
+//! following characters defaults to [`custom::LOOKAHEAD`] and is raised by
+//! implementing `Minifier<N>` for a larger `N`. The trait lives in the
+//! `custom` module, apart from the chunk minifiers [`html::Minifier`] and
+//! [`json::Minifier`]:
+//!
+//! ```rust
+//! extern crate minify;
+//! use minify::custom::{minify_str, Action, Minifier, Window};
+//!
+//! /// Collapses whitespaces into a single space
+//! #[derive(Default)]
+//! struct Whitespace;
+//!
+//! impl Minifier for Whitespace {
+//!     fn action(&mut self, window: &Window) -> Action {
+//!         let is_space = |item: char| item.is_whitespace();
+//!         if !is_space(window.current()) {
+//!             Action::Keep
+//!         } else if window.peek(1).is_some_and(is_space) {
+//!             Action::Drop
+//!         } else {
+//!             Action::Replace(" ".into())
+//!         }
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let minified = minify_str::<Whitespace>("a  \n  b");
+//! }
+//! ```
 
 #![warn(
     absolute_paths_not_starting_with_crate,
     anonymous_parameters,
-    box_pointers,
     confusable_idents,
     deprecated_in_future,
     // elided_lifetimes_in_paths,
     explicit_outlives_requirements,
-    indirect_structural_match,
     keyword_idents,
     macro_use_extern_crate,
     meta_variable_misuse,
@@ -83,7 +201,6 @@
     single_use_lifetimes,
     trivial_casts,
     trivial_numeric_casts,
-    unaligned_references,
     // unreachable_pub,
     unsafe_code,
     unstable_features,
@@ -112,8 +229,16 @@
     clippy::match_wildcard_for_single_variants
 )]
 
+pub use crate::io::unstable::CharsError;
+
+/// Minification for css content
+pub mod css;
+/// Minification for custom formats
+pub mod custom;
 /// Minification for html content
 pub mod html;
 mod io;
+/// Minification for javascript content
+pub mod js;
 /// Minifigation for json content
 pub mod json;
@@ -0,0 +1,11 @@
+// Script test
+function test ( a , b ) {
+    /* block
+       comment */
+    var c = a / b ;
+    return c
+        + 1 ;
+}
+
+var d = "a  //  b" ;
+var e = / [/ ]+ /g.test( `x  ${ d }  y` ) ;